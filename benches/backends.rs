@@ -0,0 +1,167 @@
+//! Compara os lexers e parsers habilitados sobre programas gerados, de
+//! tamanho e aninhamento crescentes.
+//!
+//! O Criterion mede tempo e vazão (bytes de código por segundo). Antes dos
+//! benchmarks, uma tabela com as alocações de uma execução de cada backend é
+//! impressa, contadas por um alocador global instrumentado.
+//!
+//!     cargo bench --bench backends
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use quest_lang::backend;
+use quest_lang::span::Spanned;
+use quest_lang::token::Token;
+
+/// Alocador do sistema contando alocações e bytes pedidos.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Alocações e bytes alocados durante `f`.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (usize, usize) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    black_box(f());
+    (
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes,
+    )
+}
+
+/// Programa plano com `statements` statements de todos os tipos.
+fn flat_program(statements: usize) -> String {
+    let mut code = String::new();
+    for i in 0..statements {
+        match i % 6 {
+            0 => writeln!(code, "move_up // passo {}", i),
+            1 => writeln!(code, "if (hero + {} < enemy * 2) {{ attack }} else {{ defend }}", i),
+            2 => writeln!(code, "while (enemy - {} && !treasure) {{ move_right jump }}", i),
+            3 => writeln!(code, "for (let i = hero; i <= {}; i += 1) {{ move_left }}", i),
+            4 => writeln!(code, "repeat ({}) {{ defend }}", i % 7),
+            _ => writeln!(code, "{{ jump attack }}"),
+        }
+        .unwrap();
+    }
+    code
+}
+
+/// `depth` ifs aninhados, com uma expressão parentizada também aninhada.
+fn nested_program(depth: usize) -> String {
+    let mut code = String::new();
+    for i in 0..depth {
+        let condition = format!("{}hero{}", "(".repeat(i % 8), " + 1)".repeat(i % 8));
+        writeln!(code, "{}if ({} > {}) {{", "    ".repeat(i), condition, i).unwrap();
+    }
+    writeln!(code, "{}attack", "    ".repeat(depth)).unwrap();
+    for i in (0..depth).rev() {
+        writeln!(code, "{}}} else {{ defend }}", "    ".repeat(i)).unwrap();
+    }
+    code
+}
+
+/// `statements` statements numa linha só, sem comentários.
+fn long_line_program(statements: usize) -> String {
+    let mut code = String::new();
+    for i in 0..statements {
+        write!(code, "if (hero < {}) {{ attack }} move_right ", i).unwrap();
+    }
+    code
+}
+
+fn inputs() -> Vec<(String, String)> {
+    let mut inputs: Vec<(String, String)> = [10, 100, 1_000, 10_000]
+        .into_iter()
+        .map(|n| (format!("flat/{}", n), flat_program(n)))
+        .collect();
+    inputs.extend(
+        [4, 16, 64]
+            .into_iter()
+            .map(|depth| (format!("nested/{}", depth), nested_program(depth))),
+    );
+    inputs.push(("long_line/10000".to_string(), long_line_program(10_000)));
+    inputs
+}
+
+/// Tokens para os parsers, do primeiro lexer habilitado.
+fn tokens(code: &str) -> Vec<Spanned<Token>> {
+    let lexer = backend::lexers().first().expect("nenhum lexer habilitado");
+    (lexer.lex)(code).expect("programa gerado deve ser válido")
+}
+
+fn print_allocation_table(inputs: &[(String, String)]) {
+    println!("\nAlocações por execução (alocações / bytes):");
+    println!("{:<14} {:<16} {:>12} {:>14}", "entrada", "backend", "alocações", "bytes");
+    for (name, code) in inputs {
+        for lexer in backend::lexers() {
+            let (count, bytes) = count_allocations(|| (lexer.lex)(code));
+            println!("{:<14} {:<16} {:>12} {:>14}", name, format!("lexer {}", lexer.name), count, bytes);
+        }
+        let tokens = tokens(code);
+        for parser in backend::parsers() {
+            let input = tokens.clone();
+            let (count, bytes) = count_allocations(|| (parser.parse)(input));
+            println!("{:<14} {:<16} {:>12} {:>14}", name, format!("parser {}", parser.name), count, bytes);
+        }
+    }
+    println!();
+}
+
+fn lexers(c: &mut Criterion) {
+    let inputs = inputs();
+    print_allocation_table(&inputs);
+
+    let mut group = c.benchmark_group("lex");
+    for (name, code) in &inputs {
+        group.throughput(Throughput::Bytes(code.len() as u64));
+        for lexer in backend::lexers() {
+            group.bench_with_input(BenchmarkId::new(lexer.name, name), code, |b, code| {
+                b.iter(|| (lexer.lex)(black_box(code)))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn parsers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, code) in &inputs() {
+        let tokens = tokens(code);
+        group.throughput(Throughput::Bytes(code.len() as u64));
+        for parser in backend::parsers() {
+            group.bench_with_input(BenchmarkId::new(parser.name, name), &tokens, |b, tokens| {
+                // A cópia dos tokens fica fora da medição
+                b.iter_batched(|| tokens.clone(), parser.parse, BatchSize::LargeInput)
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lexers, parsers);
+criterion_main!(benches);
@@ -0,0 +1,237 @@
+use crate::span::Span;
+use std::fmt;
+
+/// Statement da árvore única produzida por todos os parsers, com o trecho
+/// do código-fonte de onde veio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+impl Statement {
+    pub fn new(kind: StatementKind, span: Span) -> Self {
+        Statement { kind, span }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementKind {
+    Command(Command),
+    /// `else` é opcional e pertence sempre ao `if` mais próximo. Os ramos,
+    /// como os corpos dos laços, são um statement qualquer: um `Block` com
+    /// chaves ou, sem elas, um statement só (em `else if`, o `If` aninhado).
+    If {
+        condition: Expression,
+        then_branch: Box<Statement>,
+        else_branch: Option<Box<Statement>>,
+    },
+    While {
+        condition: Expression,
+        body: Box<Statement>,
+    },
+    /// `init` é um `let` ou uma atribuição, visível na condição, no corpo e
+    /// em `update`, que é uma atribuição. Nenhum dos dois leva `;` próprio.
+    For {
+        init: Box<Statement>,
+        condition: Expression,
+        update: Box<Statement>,
+        body: Box<Statement>,
+    },
+    /// Repete o corpo `count` vezes; `count` é avaliado uma vez, antes da
+    /// primeira repetição, e um valor negativo conta como zero.
+    Repeat {
+        count: Expression,
+        body: Box<Statement>,
+    },
+    /// `let name = value;` declara a variável no bloco atual, escondendo
+    /// outra de mesmo nome de fora do bloco até o fim dele.
+    Let {
+        name: String,
+        value: Expression,
+    },
+    /// `name = value;` ou `name += value;`, sobre a variável declarada mais
+    /// próxima.
+    Assign {
+        name: String,
+        op: AssignOp,
+        value: Expression,
+    },
+    Block(Vec<Statement>),
+    /// Trecho que não pôde ser parseado; só aparece nas ASTs parciais
+    /// produzidas pela recuperação de erros.
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Command {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Attack,
+    Defend,
+}
+
+/// Expressão com o trecho do código-fonte de onde veio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub kind: ExpressionKind,
+    pub span: Span,
+}
+
+impl Expression {
+    pub fn new(kind: ExpressionKind, span: Span) -> Self {
+        Expression { kind, span }
+    }
+
+    /// Operação binária cobrindo os dois operandos.
+    pub fn binary(left: Expression, op: BinaryOp, right: Expression) -> Self {
+        let span = left.span.merge(right.span);
+        Expression::new(
+            ExpressionKind::Binary {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            },
+            span,
+        )
+    }
+
+    /// Operação unária cobrindo o operador (em `op_span`) e o operando.
+    pub fn unary(op: UnaryOp, op_span: Span, operand: Expression) -> Self {
+        let span = op_span.merge(operand.span);
+        Expression::new(
+            ExpressionKind::Unary {
+                op,
+                operand: Box::new(operand),
+            },
+            span,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionKind {
+    Identifier(String),
+    Number(i64),
+    Binary {
+        left: Box<Expression>,
+        op: BinaryOp,
+        right: Box<Expression>,
+    },
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expression>,
+    },
+    /// Expressão que não pôde ser parseada (recuperação de erros).
+    Error,
+}
+
+/// Operadores binários, do mais forte para o mais fraco:
+/// `* /`, `+ -`, comparações, `&&`, `||`. As comparações não associam
+/// (`a < b < c` é erro); os demais associam à esquerda.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    And,
+    Or,
+}
+
+/// Operadores de atribuição.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssignOp {
+    /// `=`
+    Set,
+    /// `+=`
+    Add,
+}
+
+/// Operadores prefixos (`!` e `-`), mais fortes que qualquer operador binário;
+/// podem se repetir (`!!x`, `- -1`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    Not,
+    Negate,
+}
+
+impl BinaryOp {
+    /// Nível de precedência: quanto maior, mais forte o operador.
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Equal
+            | BinaryOp::NotEqual
+            | BinaryOp::Less
+            | BinaryOp::LessEqual
+            | BinaryOp::Greater
+            | BinaryOp::GreaterEqual => 3,
+            BinaryOp::Plus | BinaryOp::Minus => 4,
+            BinaryOp::Multiply | BinaryOp::Divide => 5,
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Command::MoveUp => "move_up",
+            Command::MoveDown => "move_down",
+            Command::MoveLeft => "move_left",
+            Command::MoveRight => "move_right",
+            Command::Jump => "jump",
+            Command::Attack => "attack",
+            Command::Defend => "defend",
+        };
+        f.write_str(text)
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            BinaryOp::Plus => "+",
+            BinaryOp::Minus => "-",
+            BinaryOp::Multiply => "*",
+            BinaryOp::Divide => "/",
+            BinaryOp::Equal => "==",
+            BinaryOp::NotEqual => "!=",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEqual => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEqual => ">=",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+        };
+        f.write_str(text)
+    }
+}
+
+impl fmt::Display for AssignOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssignOp::Set => f.write_str("="),
+            AssignOp::Add => f.write_str("+="),
+        }
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnaryOp::Not => f.write_str("!"),
+            UnaryOp::Negate => f.write_str("-"),
+        }
+    }
+}
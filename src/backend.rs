@@ -0,0 +1,72 @@
+use crate::ast::Statement;
+use crate::error::{LexError, ParseError};
+use crate::span::Spanned;
+use crate::token::Token;
+
+pub type LexResult = Result<Vec<Spanned<Token>>, Vec<LexError>>;
+pub type ParseResult = Result<Vec<Statement>, Vec<ParseError>>;
+
+/// Analisador léxico disponível nesta compilação.
+#[derive(Debug, Clone, Copy)]
+pub struct LexerBackend {
+    pub name: &'static str,
+    pub lex: fn(&str) -> LexResult,
+}
+
+/// Analisador sintático disponível nesta compilação.
+#[derive(Debug, Clone, Copy)]
+pub struct ParserBackend {
+    pub name: &'static str,
+    pub parse: fn(Vec<Spanned<Token>>) -> ParseResult,
+}
+
+/// Lexers habilitados pelas features do crate, na ordem do menu.
+static LEXERS: &[LexerBackend] = &[
+    #[cfg(feature = "manual-lexer")]
+    LexerBackend {
+        name: "Manual",
+        lex: crate::lexer_manual::lexer_impl::lex,
+    },
+    #[cfg(feature = "logos-lexer")]
+    LexerBackend {
+        name: "Logos",
+        lex: crate::lexer::lexer_impl::lex,
+    },
+];
+
+/// Parsers habilitados pelas features do crate, na ordem do menu.
+static PARSERS: &[ParserBackend] = &[
+    #[cfg(feature = "manual-parser")]
+    ParserBackend {
+        name: "Manual",
+        parse: crate::parser_manual::parser_impl::parse,
+    },
+    #[cfg(feature = "nom-parser")]
+    ParserBackend {
+        name: "Nom",
+        parse: crate::parser_nom::parser_impl::parse,
+    },
+    #[cfg(feature = "chumsky-parser")]
+    ParserBackend {
+        name: "Chumsky",
+        parse: crate::parser::parser_impl::parse_tokens,
+    },
+];
+
+pub fn lexers() -> &'static [LexerBackend] {
+    LEXERS
+}
+
+pub fn parsers() -> &'static [ParserBackend] {
+    PARSERS
+}
+
+/// Procura um lexer pelo nome, sem diferenciar maiúsculas.
+pub fn find_lexer(name: &str) -> Option<LexerBackend> {
+    lexers().iter().find(|b| b.name.eq_ignore_ascii_case(name)).copied()
+}
+
+/// Procura um parser pelo nome, sem diferenciar maiúsculas.
+pub fn find_parser(name: &str) -> Option<ParserBackend> {
+    parsers().iter().find(|b| b.name.eq_ignore_ascii_case(name)).copied()
+}
@@ -0,0 +1,9 @@
+//! Árvore sintática concreta (CST), no estilo green/red do rowan.
+//!
+//! Diferente da AST, a CST guarda tudo o que está no código: parênteses,
+//! espaços, comentários e trechos inválidos (em nós `Error`). O texto da
+//! árvore é sempre idêntico, byte a byte, ao código de entrada.
+
+pub mod lower;
+pub mod parser;
+pub mod syntax;
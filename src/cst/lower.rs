@@ -0,0 +1,259 @@
+use crate::ast::*;
+use crate::cst::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use crate::span::Span;
+
+/// Converte a CST na AST tipada. Trechos com erro viram nós `Error`, como na
+/// recuperação do parser Chumsky.
+pub fn lower(root: &SyntaxNode) -> Vec<Statement> {
+    statements(root)
+}
+
+fn span(node: &SyntaxNode) -> Span {
+    let range = node.text_range();
+    Span::new(range.start().into(), range.end().into())
+}
+
+fn token_span(token: &SyntaxToken) -> Span {
+    let range = token.text_range();
+    Span::new(range.start().into(), range.end().into())
+}
+
+/// Trecho vazio no fim do nó, para o que faltou nele.
+fn missing(node: &SyntaxNode) -> Span {
+    let end = span(node).end;
+    Span::new(end, end)
+}
+
+fn is_statement(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Block
+            | SyntaxKind::IfStmt
+            | SyntaxKind::WhileStmt
+            | SyntaxKind::ForStmt
+            | SyntaxKind::RepeatStmt
+            | SyntaxKind::CommandStmt
+            | SyntaxKind::LetStmt
+            | SyntaxKind::AssignStmt
+            | SyntaxKind::Error
+    )
+}
+
+fn is_expression(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Literal
+            | SyntaxKind::NameRef
+            | SyntaxKind::ParenExpr
+            | SyntaxKind::UnaryExpr
+            | SyntaxKind::BinaryExpr
+    )
+}
+
+/// Tokens filhos diretos do nó, sem trivia nem erros léxicos.
+fn tokens(node: &SyntaxNode) -> impl Iterator<Item = SyntaxToken> {
+    node.children_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| !token.kind().is_trivia() && token.kind() != SyntaxKind::ErrorToken)
+}
+
+fn first_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    tokens(node).next()
+}
+
+/// Nome declarado ou atribuído pelo nó.
+fn name(node: &SyntaxNode) -> Option<String> {
+    tokens(node)
+        .find(|token| token.kind() == SyntaxKind::Identifier)
+        .map(|token| token.text().to_string())
+}
+
+fn statements(node: &SyntaxNode) -> Vec<Statement> {
+    node.children()
+        .filter(|child| is_statement(child.kind()))
+        .map(|child| statement(&child))
+        .collect()
+}
+
+/// Statement filho do nó (corpo de if, else, while e for), ou `Error` se faltar.
+fn child_statement(node: &SyntaxNode) -> Box<Statement> {
+    let statement = match node.children().find(|child| is_statement(child.kind())) {
+        Some(child) => statement(&child),
+        None => Statement::new(StatementKind::Error, missing(node)),
+    };
+    Box::new(statement)
+}
+
+fn statement(node: &SyntaxNode) -> Statement {
+    let kind = match node.kind() {
+        SyntaxKind::Block => StatementKind::Block(statements(node)),
+        SyntaxKind::CommandStmt => match first_token(node).map(|t| t.kind()) {
+            Some(SyntaxKind::MoveUp) => StatementKind::Command(Command::MoveUp),
+            Some(SyntaxKind::MoveDown) => StatementKind::Command(Command::MoveDown),
+            Some(SyntaxKind::MoveLeft) => StatementKind::Command(Command::MoveLeft),
+            Some(SyntaxKind::MoveRight) => StatementKind::Command(Command::MoveRight),
+            Some(SyntaxKind::Jump) => StatementKind::Command(Command::Jump),
+            Some(SyntaxKind::Attack) => StatementKind::Command(Command::Attack),
+            Some(SyntaxKind::Defend) => StatementKind::Command(Command::Defend),
+            _ => StatementKind::Error,
+        },
+        SyntaxKind::IfStmt => {
+            let else_branch = node
+                .children()
+                .find(|child| child.kind() == SyntaxKind::ElseBranch)
+                .map(|branch| child_statement(&branch));
+            StatementKind::If {
+                condition: condition(node),
+                then_branch: child_statement(node),
+                else_branch,
+            }
+        }
+        SyntaxKind::WhileStmt => StatementKind::While {
+            condition: condition(node),
+            body: child_statement(node),
+        },
+        SyntaxKind::ForStmt => {
+            let (init, condition, update) = match node
+                .children()
+                .find(|child| child.kind() == SyntaxKind::ForHeader)
+            {
+                Some(header) => for_header(&header),
+                None => (
+                    Box::new(Statement::new(StatementKind::Error, missing(node))),
+                    Expression::new(ExpressionKind::Error, missing(node)),
+                    Box::new(Statement::new(StatementKind::Error, missing(node))),
+                ),
+            };
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body: child_statement(node),
+            }
+        }
+        SyntaxKind::RepeatStmt => StatementKind::Repeat {
+            count: condition(node),
+            body: child_statement(node),
+        },
+        SyntaxKind::LetStmt => match name(node) {
+            Some(name) => StatementKind::Let {
+                name,
+                value: child_expression(node, 0),
+            },
+            None => StatementKind::Error,
+        },
+        SyntaxKind::AssignStmt => {
+            let op = tokens(node).find_map(|token| match token.kind() {
+                SyntaxKind::Equal => Some(AssignOp::Set),
+                SyntaxKind::PlusEqual => Some(AssignOp::Add),
+                _ => None,
+            });
+            match (name(node), op) {
+                (Some(name), Some(op)) => StatementKind::Assign {
+                    name,
+                    op,
+                    value: child_expression(node, 0),
+                },
+                _ => StatementKind::Error,
+            }
+        }
+        _ => StatementKind::Error,
+    };
+    Statement::new(kind, span(node))
+}
+
+/// Expressão do nó `Condition` de if, while e repeat.
+fn condition(node: &SyntaxNode) -> Expression {
+    match node
+        .children()
+        .find(|child| child.kind() == SyntaxKind::Condition)
+    {
+        Some(condition) => child_expression(&condition, 0),
+        None => Expression::new(ExpressionKind::Error, missing(node)),
+    }
+}
+
+/// Início, condição e atualização do for, separados pelos `;` (qualquer um
+/// pode faltar).
+fn for_header(header: &SyntaxNode) -> (Box<Statement>, Expression, Box<Statement>) {
+    let mut init = None;
+    let mut condition = None;
+    let mut update = None;
+    let mut index = 0;
+    for element in header.children_with_tokens() {
+        match element {
+            rowan::NodeOrToken::Token(token) if token.kind() == SyntaxKind::Semicolon => {
+                index += 1;
+            }
+            rowan::NodeOrToken::Node(node) if index == 1 && is_expression(node.kind()) => {
+                condition = Some(expression(&node));
+            }
+            rowan::NodeOrToken::Node(node) if index != 1 && is_statement(node.kind()) => {
+                let part = if index == 0 { &mut init } else { &mut update };
+                *part = Some(Box::new(statement(&node)));
+            }
+            _ => {}
+        }
+    }
+    let missing_statement = || Box::new(Statement::new(StatementKind::Error, missing(header)));
+    (
+        init.unwrap_or_else(missing_statement),
+        condition.unwrap_or_else(|| Expression::new(ExpressionKind::Error, missing(header))),
+        update.unwrap_or_else(missing_statement),
+    )
+}
+
+/// N-ésima expressão filha do nó, ou `Error` se faltar.
+fn child_expression(node: &SyntaxNode, n: usize) -> Expression {
+    match node.children().filter(|child| is_expression(child.kind())).nth(n) {
+        Some(child) => expression(&child),
+        None => Expression::new(ExpressionKind::Error, missing(node)),
+    }
+}
+
+fn expression(node: &SyntaxNode) -> Expression {
+    match node.kind() {
+        SyntaxKind::Literal => {
+            let kind = first_token(node)
+                .and_then(|t| t.text().parse().ok())
+                .map_or(ExpressionKind::Error, ExpressionKind::Number);
+            Expression::new(kind, span(node))
+        }
+        SyntaxKind::NameRef => {
+            let kind = first_token(node)
+                .map_or(ExpressionKind::Error, |t| ExpressionKind::Identifier(t.text().to_string()));
+            Expression::new(kind, span(node))
+        }
+        // Os parênteses só agrupam: a AST fica com a expressão de dentro
+        SyntaxKind::ParenExpr => child_expression(node, 0),
+        SyntaxKind::UnaryExpr => {
+            let operator = first_token(node);
+            let op = match operator.as_ref().map(|t| t.kind()) {
+                Some(SyntaxKind::LogicalNot) => UnaryOp::Not,
+                Some(SyntaxKind::Minus) => UnaryOp::Negate,
+                _ => return Expression::new(ExpressionKind::Error, span(node)),
+            };
+            let op_span = operator.as_ref().map_or(span(node), token_span);
+            Expression::unary(op, op_span, child_expression(node, 0))
+        }
+        SyntaxKind::BinaryExpr => {
+            let op = match first_token(node).map(|t| t.kind()) {
+                Some(SyntaxKind::Plus) => BinaryOp::Plus,
+                Some(SyntaxKind::Minus) => BinaryOp::Minus,
+                Some(SyntaxKind::Asterisk) => BinaryOp::Multiply,
+                Some(SyntaxKind::Slash) => BinaryOp::Divide,
+                Some(SyntaxKind::EqualEqual) => BinaryOp::Equal,
+                Some(SyntaxKind::NotEqual) => BinaryOp::NotEqual,
+                Some(SyntaxKind::Less) => BinaryOp::Less,
+                Some(SyntaxKind::LessEqual) => BinaryOp::LessEqual,
+                Some(SyntaxKind::Greater) => BinaryOp::Greater,
+                Some(SyntaxKind::GreaterEqual) => BinaryOp::GreaterEqual,
+                Some(SyntaxKind::LogicalAnd) => BinaryOp::And,
+                Some(SyntaxKind::LogicalOr) => BinaryOp::Or,
+                _ => return Expression::new(ExpressionKind::Error, span(node)),
+            };
+            Expression::binary(child_expression(node, 0), op, child_expression(node, 1))
+        }
+        _ => Expression::new(ExpressionKind::Error, span(node)),
+    }
+}
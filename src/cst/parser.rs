@@ -0,0 +1,531 @@
+use crate::ast::Statement;
+use crate::cst::lower;
+use crate::cst::syntax::{SyntaxKind, SyntaxNode};
+use crate::error::{Expected, ParseError, QuestError};
+use crate::lexer::lexer_impl::Lexer;
+use crate::span::{Position, Span};
+use crate::token::Token;
+use rowan::{Checkpoint, GreenNode, GreenNodeBuilder};
+
+/// Resultado do parse sem perdas: a árvore verde e os erros encontrados.
+/// A árvore existe mesmo com erros; os trechos inválidos ficam em nós `Error`.
+#[derive(Debug, Clone)]
+pub struct Parse {
+    green: GreenNode,
+    errors: Vec<QuestError>,
+}
+
+impl Parse {
+    pub fn green(&self) -> &GreenNode {
+        &self.green
+    }
+
+    /// Raiz da árvore vermelha, com posições e navegação para os pais.
+    pub fn syntax(&self) -> SyntaxNode {
+        SyntaxNode::new_root(self.green.clone())
+    }
+
+    pub fn errors(&self) -> &[QuestError] {
+        &self.errors
+    }
+
+    /// Converte a árvore para a AST tipada.
+    pub fn ast(&self) -> Vec<Statement> {
+        lower::lower(&self.syntax())
+    }
+}
+
+/// Token como aparece na CST: além dos tokens da linguagem, inclui os
+/// espaços e o texto rejeitado pelo lexer.
+struct RawToken<'a> {
+    kind: SyntaxKind,
+    text: &'a str,
+    span: Span,
+    /// Token da linguagem; `None` para espaços e erros léxicos.
+    value: Option<Token>,
+}
+
+impl RawToken<'_> {
+    /// Tokens que a gramática pula: trivia e erros léxicos (já reportados).
+    fn is_skipped(&self) -> bool {
+        self.kind.is_trivia() || self.kind == SyntaxKind::ErrorToken
+    }
+}
+
+/// Monta a CST do código. Nunca falha: o texto inteiro vai para a árvore.
+pub fn parse(source: &str) -> Parse {
+    let (tokens, errors) = tokenize(source);
+    let mut parser = Parser {
+        source,
+        tokens,
+        pos: 0,
+        builder: GreenNodeBuilder::new(),
+        errors,
+    };
+    parser.root();
+    Parse {
+        green: parser.builder.finish(),
+        errors: parser.errors,
+    }
+}
+
+/// Tokeniza com o Logos, preenchendo os intervalos entre tokens (os espaços
+/// que o lexer pula) para que nenhum byte se perca.
+fn tokenize(source: &str) -> (Vec<RawToken<'_>>, Vec<QuestError>) {
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    let mut offset = 0;
+    for item in Lexer::new(source) {
+        let (kind, span, value) = match item {
+            Ok(token) => (SyntaxKind::from(&token.value), token.span, Some(token.value)),
+            Err(error) => {
+                let span = error.span;
+                errors.push(QuestError::Lex(error));
+                (SyntaxKind::ErrorToken, span, None)
+            }
+        };
+        if span.start > offset {
+            tokens.push(whitespace(source, offset, span.start));
+        }
+        tokens.push(RawToken {
+            kind,
+            text: &source[span.start..span.end],
+            span,
+            value,
+        });
+        offset = span.end;
+    }
+    if offset < source.len() {
+        tokens.push(whitespace(source, offset, source.len()));
+    }
+    (tokens, errors)
+}
+
+fn whitespace(source: &str, start: usize, end: usize) -> RawToken<'_> {
+    RawToken {
+        kind: SyntaxKind::Whitespace,
+        text: &source[start..end],
+        span: Span::new(start, end),
+        value: None,
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<RawToken<'a>>,
+    pos: usize,
+    builder: GreenNodeBuilder<'static>,
+    errors: Vec<QuestError>,
+}
+
+impl Parser<'_> {
+    /// Programa: statement*. Trivia das pontas também fica na raiz.
+    fn root(&mut self) {
+        self.builder.start_node(SyntaxKind::Root.into());
+        while self.current().is_some() {
+            self.statement();
+        }
+        self.trivia();
+        self.builder.finish_node();
+    }
+
+    fn statement(&mut self) {
+        match self.current() {
+            Some(SyntaxKind::If) => self.if_stmt(),
+            Some(SyntaxKind::While) => self.while_stmt(),
+            Some(SyntaxKind::For) => self.for_stmt(),
+            Some(SyntaxKind::Repeat) => self.repeat_stmt(),
+            Some(SyntaxKind::LBrace) => self.block(),
+            Some(SyntaxKind::Let) => {
+                self.let_stmt(true);
+            }
+            Some(SyntaxKind::Identifier) => {
+                self.assign_stmt(true);
+            }
+            Some(kind) if kind.is_command() => {
+                self.start(SyntaxKind::CommandStmt);
+                self.bump();
+                self.finish();
+            }
+            _ => self.error_statement(),
+        }
+    }
+
+    /// Junta num nó `Error` os tokens até o próximo início de statement ou
+    /// delimitador de bloco. Consome ao menos um token.
+    fn error_statement(&mut self) {
+        self.error(vec![Expected::Statement]);
+        self.start(SyntaxKind::Error);
+        self.bump();
+        while let Some(kind) = self.current() {
+            if starts_statement(kind) || kind == SyntaxKind::RBrace {
+                break;
+            }
+            self.bump();
+        }
+        self.finish();
+    }
+
+    /// Junta num nó `Error` os tokens até o `;`, o `)` do cabeçalho do for, um
+    /// delimitador de bloco ou o próximo statement que não comece por nome.
+    fn skip_to_statement_end(&mut self) {
+        let at_end = |kind: SyntaxKind| {
+            matches!(kind, SyntaxKind::Semicolon | SyntaxKind::RParen | SyntaxKind::RBrace)
+                || (starts_statement(kind) && kind != SyntaxKind::Identifier)
+        };
+        if self.current().is_none_or(at_end) {
+            return;
+        }
+        self.start(SyntaxKind::Error);
+        while self.current().is_some_and(|kind| !at_end(kind)) {
+            self.bump();
+        }
+        self.finish();
+    }
+
+    /// Bloco: { statement* }
+    fn block(&mut self) {
+        self.start(SyntaxKind::Block);
+        self.bump();
+        loop {
+            match self.current() {
+                Some(SyntaxKind::RBrace) => {
+                    self.bump();
+                    break;
+                }
+                Some(_) => self.statement(),
+                None => {
+                    self.error(vec![Expected::Token(Token::RBrace)]);
+                    break;
+                }
+            }
+        }
+        self.finish();
+    }
+
+    /// Corpo de if, else, while, repeat e for: um statement, ou um erro se
+    /// ele faltar.
+    fn body(&mut self) {
+        if self.current().is_some_and(starts_statement) {
+            self.statement();
+        } else {
+            self.error(vec![Expected::Statement]);
+        }
+    }
+
+    /// if ( expr ) statement [else statement]
+    fn if_stmt(&mut self) {
+        self.start(SyntaxKind::IfStmt);
+        self.bump();
+        self.condition();
+        self.body();
+        if self.at(SyntaxKind::Else) {
+            self.start(SyntaxKind::ElseBranch);
+            self.bump();
+            self.body();
+            self.finish();
+        }
+        self.finish();
+    }
+
+    /// while ( expr ) statement
+    fn while_stmt(&mut self) {
+        self.start(SyntaxKind::WhileStmt);
+        self.bump();
+        self.condition();
+        self.body();
+        self.finish();
+    }
+
+    /// repeat ( expr ) statement
+    fn repeat_stmt(&mut self) {
+        self.start(SyntaxKind::RepeatStmt);
+        self.bump();
+        self.condition();
+        self.body();
+        self.finish();
+    }
+
+    /// for ( (let | atribuição) ; expr ; atribuição ) statement
+    fn for_stmt(&mut self) {
+        self.start(SyntaxKind::ForStmt);
+        self.bump();
+        self.start(SyntaxKind::ForHeader);
+        self.expect(SyntaxKind::LParen);
+        match self.current() {
+            Some(SyntaxKind::Let) => self.let_stmt(false),
+            Some(SyntaxKind::Identifier) => self.assign_stmt(false),
+            _ => self.error(vec![Expected::Token(Token::Let), Expected::Identifier]),
+        }
+        self.expect(SyntaxKind::Semicolon);
+        self.expression();
+        self.expect(SyntaxKind::Semicolon);
+        if self.at(SyntaxKind::Identifier) {
+            self.assign_stmt(false);
+        } else {
+            self.error(vec![Expected::Identifier]);
+        }
+        self.expect(SyntaxKind::RParen);
+        self.finish();
+        self.body();
+        self.finish();
+    }
+
+    /// let nome = expr [;]. O `;` fica de fora no cabeçalho do for.
+    fn let_stmt(&mut self, semicolon: bool) {
+        self.start(SyntaxKind::LetStmt);
+        self.bump();
+        if self.at(SyntaxKind::Identifier) {
+            self.bump();
+        } else {
+            self.error(vec![Expected::Identifier]);
+        }
+        self.expect(SyntaxKind::Equal);
+        self.expression();
+        if semicolon {
+            self.expect(SyntaxKind::Semicolon);
+        }
+        self.finish();
+    }
+
+    /// nome (= | +=) expr [;]
+    fn assign_stmt(&mut self, semicolon: bool) {
+        self.start(SyntaxKind::AssignStmt);
+        self.bump();
+        if self.at(SyntaxKind::Equal) || self.at(SyntaxKind::PlusEqual) {
+            self.bump();
+            self.expression();
+        } else {
+            // Sem o operador, o resto não é uma atribuição: vai inteiro para
+            // um nó `Error`, sem um erro para cada token
+            self.error(vec![Expected::Token(Token::Equal), Expected::Token(Token::PlusEqual)]);
+            self.skip_to_statement_end();
+            if semicolon && self.at(SyntaxKind::Semicolon) {
+                self.bump();
+            }
+            self.finish();
+            return;
+        }
+        if semicolon {
+            self.expect(SyntaxKind::Semicolon);
+        }
+        self.finish();
+    }
+
+    /// ( expr ), usado pelas condições de if e while e pela contagem do repeat
+    fn condition(&mut self) {
+        self.start(SyntaxKind::Condition);
+        self.expect(SyntaxKind::LParen);
+        self.expression();
+        self.expect(SyntaxKind::RParen);
+        self.finish();
+    }
+
+    /// Um método por nível de [`BinaryOp::precedence`], do mais fraco ao mais
+    /// forte.
+    ///
+    /// [`BinaryOp::precedence`]: crate::ast::BinaryOp::precedence
+    fn expression(&mut self) {
+        self.binary_level(Self::and, &[SyntaxKind::LogicalOr]);
+    }
+
+    fn and(&mut self) {
+        self.binary_level(Self::comparison, &[SyntaxKind::LogicalAnd]);
+    }
+
+    fn comparison(&mut self) {
+        let checkpoint = self.checkpoint();
+        self.sum();
+        if self.current().is_some_and(is_comparison) {
+            self.builder
+                .start_node_at(checkpoint, SyntaxKind::BinaryExpr.into());
+            self.bump();
+            self.sum();
+            self.finish();
+        }
+    }
+
+    fn sum(&mut self) {
+        self.binary_level(Self::product, &[SyntaxKind::Plus, SyntaxKind::Minus]);
+    }
+
+    fn product(&mut self) {
+        self.binary_level(Self::unary, &[SyntaxKind::Asterisk, SyntaxKind::Slash]);
+    }
+
+    /// Nível associativo à esquerda: cada operador embrulha o que já foi lido.
+    fn binary_level(&mut self, operand: fn(&mut Self), operators: &[SyntaxKind]) {
+        let checkpoint = self.checkpoint();
+        operand(self);
+        while self.current().is_some_and(|kind| operators.contains(&kind)) {
+            self.builder
+                .start_node_at(checkpoint, SyntaxKind::BinaryExpr.into());
+            self.bump();
+            operand(self);
+            self.finish();
+        }
+    }
+
+    fn unary(&mut self) {
+        if self.at(SyntaxKind::LogicalNot) || self.at(SyntaxKind::Minus) {
+            self.start(SyntaxKind::UnaryExpr);
+            self.bump();
+            self.unary();
+            self.finish();
+        } else {
+            self.term();
+        }
+    }
+
+    /// Número, identificador ou expressão entre parênteses. Se não houver
+    /// termo, registra o erro sem consumir nada.
+    fn term(&mut self) {
+        match self.current() {
+            Some(SyntaxKind::Number) => {
+                self.start(SyntaxKind::Literal);
+                self.bump();
+                self.finish();
+            }
+            Some(SyntaxKind::Identifier) => {
+                self.start(SyntaxKind::NameRef);
+                self.bump();
+                self.finish();
+            }
+            Some(SyntaxKind::LParen) => {
+                self.start(SyntaxKind::ParenExpr);
+                self.bump();
+                self.expression();
+                self.expect(SyntaxKind::RParen);
+                self.finish();
+            }
+            _ => self.error(vec![Expected::Expression]),
+        }
+    }
+
+    // ---------------------------------------------
+    // Funções auxiliares sobre tokens e nós
+    // ---------------------------------------------
+
+    /// Índice do próximo token significativo.
+    fn next_index(&self) -> Option<usize> {
+        (self.pos..self.tokens.len()).find(|&i| !self.tokens[i].is_skipped())
+    }
+
+    /// Tipo do próximo token significativo, sem consumi-lo.
+    fn current(&self) -> Option<SyntaxKind> {
+        self.next_index().map(|i| self.tokens[i].kind)
+    }
+
+    fn at(&self, kind: SyntaxKind) -> bool {
+        self.current() == Some(kind)
+    }
+
+    /// Emite a trivia pendente no nó atual.
+    fn trivia(&mut self) {
+        while self.pos < self.tokens.len() && self.tokens[self.pos].is_skipped() {
+            self.emit();
+        }
+    }
+
+    /// Consome o próximo token significativo, com a trivia que o precede.
+    fn bump(&mut self) {
+        self.trivia();
+        if self.pos < self.tokens.len() {
+            self.emit();
+        }
+    }
+
+    fn emit(&mut self) {
+        let token = &self.tokens[self.pos];
+        self.builder.token(token.kind.into(), token.text);
+        self.pos += 1;
+    }
+
+    /// Consome o token esperado ou registra o erro, sem consumir nada.
+    fn expect(&mut self, kind: SyntaxKind) {
+        if self.at(kind) {
+            self.bump();
+        } else if let Some(token) = kind.to_token() {
+            self.error(vec![Expected::Token(token)]);
+        }
+    }
+
+    /// Abre um nó; a trivia anterior fica fora dele.
+    fn start(&mut self, kind: SyntaxKind) {
+        self.trivia();
+        self.builder.start_node(kind.into());
+    }
+
+    fn checkpoint(&mut self) -> Checkpoint {
+        self.trivia();
+        self.builder.checkpoint()
+    }
+
+    fn finish(&mut self) {
+        self.builder.finish_node();
+    }
+
+    /// Registra um erro no próximo token significativo (ou no fim da entrada),
+    /// a menos que já haja um erro sintático ali.
+    fn error(&mut self, expected: Vec<Expected>) {
+        let error = match self.next_index().map(|i| &self.tokens[i]) {
+            Some(RawToken {
+                value: Some(found),
+                span,
+                ..
+            }) => ParseError::UnexpectedToken {
+                expected,
+                found: found.clone(),
+                span: *span,
+                position: Position::of(self.source, span.start),
+            },
+            _ => {
+                // O fim da entrada é marcado logo após o último token significativo
+                let end = self
+                    .tokens
+                    .iter()
+                    .rev()
+                    .find(|t| !t.is_skipped())
+                    .map_or(0, |t| t.span.end);
+                ParseError::UnexpectedEof {
+                    expected,
+                    span: Span::new(end, end),
+                    position: Position::of(self.source, end),
+                }
+            }
+        };
+        let last = self.errors.iter().rev().find_map(|error| match error {
+            QuestError::Parse(error) => Some(error.span().start),
+            _ => None,
+        });
+        if last != Some(error.span().start) {
+            self.errors.push(QuestError::Parse(error));
+        }
+    }
+}
+
+fn starts_statement(kind: SyntaxKind) -> bool {
+    kind.is_command()
+        || matches!(
+            kind,
+            SyntaxKind::If
+                | SyntaxKind::While
+                | SyntaxKind::For
+                | SyntaxKind::Repeat
+                | SyntaxKind::Let
+                | SyntaxKind::Identifier
+                | SyntaxKind::LBrace
+        )
+}
+
+fn is_comparison(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::EqualEqual
+            | SyntaxKind::NotEqual
+            | SyntaxKind::Less
+            | SyntaxKind::LessEqual
+            | SyntaxKind::Greater
+            | SyntaxKind::GreaterEqual
+    )
+}
@@ -0,0 +1,206 @@
+use crate::token::Token;
+
+/// Tipo de cada token e nó da CST.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u16)]
+pub enum SyntaxKind {
+    // Tokens, na mesma ordem de `Token`
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Attack,
+    Defend,
+    If,
+    Else,
+    While,
+    For,
+    Repeat,
+    Let,
+    Plus,
+    Minus,
+    Asterisk,
+    Slash,
+    LogicalAnd,
+    LogicalOr,
+    LogicalNot,
+    EqualEqual,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    PlusEqual,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Semicolon,
+    Number,
+    Identifier,
+
+    // Trivia e texto que o lexer rejeitou
+    Comment,
+    Whitespace,
+    ErrorToken,
+
+    // Nós
+    Root,
+    Block,
+    IfStmt,
+    /// `else` seguido do bloco ou do if encadeado.
+    ElseBranch,
+    WhileStmt,
+    ForStmt,
+    /// `( init ; condition ; update )` do for.
+    ForHeader,
+    RepeatStmt,
+    /// `( expr )` de if, while e repeat.
+    Condition,
+    CommandStmt,
+    /// `let nome = expr`, com o `;` quando fora do cabeçalho do for.
+    LetStmt,
+    /// `nome = expr` ou `nome += expr`, idem.
+    AssignStmt,
+    ParenExpr,
+    BinaryExpr,
+    UnaryExpr,
+    Literal,
+    NameRef,
+    /// Trecho que não pôde ser parseado.
+    Error,
+}
+
+impl SyntaxKind {
+    /// Espaços e comentários: ignorados pela gramática, mas mantidos na árvore.
+    pub fn is_trivia(self) -> bool {
+        matches!(self, SyntaxKind::Whitespace | SyntaxKind::Comment)
+    }
+
+    pub fn is_command(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::MoveUp
+                | SyntaxKind::MoveDown
+                | SyntaxKind::MoveLeft
+                | SyntaxKind::MoveRight
+                | SyntaxKind::Jump
+                | SyntaxKind::Attack
+                | SyntaxKind::Defend
+        )
+    }
+
+    /// Token que este tipo representa, quando ele não carrega valor.
+    pub fn to_token(self) -> Option<Token> {
+        let token = match self {
+            SyntaxKind::MoveUp => Token::MoveUp,
+            SyntaxKind::MoveDown => Token::MoveDown,
+            SyntaxKind::MoveLeft => Token::MoveLeft,
+            SyntaxKind::MoveRight => Token::MoveRight,
+            SyntaxKind::Jump => Token::Jump,
+            SyntaxKind::Attack => Token::Attack,
+            SyntaxKind::Defend => Token::Defend,
+            SyntaxKind::If => Token::If,
+            SyntaxKind::Else => Token::Else,
+            SyntaxKind::While => Token::While,
+            SyntaxKind::For => Token::For,
+            SyntaxKind::Repeat => Token::Repeat,
+            SyntaxKind::Let => Token::Let,
+            SyntaxKind::Plus => Token::Plus,
+            SyntaxKind::Minus => Token::Minus,
+            SyntaxKind::Asterisk => Token::Asterisk,
+            SyntaxKind::Slash => Token::Slash,
+            SyntaxKind::LogicalAnd => Token::LogicalAnd,
+            SyntaxKind::LogicalOr => Token::LogicalOr,
+            SyntaxKind::LogicalNot => Token::LogicalNot,
+            SyntaxKind::EqualEqual => Token::EqualEqual,
+            SyntaxKind::NotEqual => Token::NotEqual,
+            SyntaxKind::Less => Token::Less,
+            SyntaxKind::LessEqual => Token::LessEqual,
+            SyntaxKind::Greater => Token::Greater,
+            SyntaxKind::GreaterEqual => Token::GreaterEqual,
+            SyntaxKind::Equal => Token::Equal,
+            SyntaxKind::PlusEqual => Token::PlusEqual,
+            SyntaxKind::LParen => Token::LParen,
+            SyntaxKind::RParen => Token::RParen,
+            SyntaxKind::LBrace => Token::LBrace,
+            SyntaxKind::RBrace => Token::RBrace,
+            SyntaxKind::Semicolon => Token::Semicolon,
+            _ => return None,
+        };
+        Some(token)
+    }
+}
+
+impl From<&Token> for SyntaxKind {
+    fn from(token: &Token) -> Self {
+        match token {
+            Token::MoveUp => SyntaxKind::MoveUp,
+            Token::MoveDown => SyntaxKind::MoveDown,
+            Token::MoveLeft => SyntaxKind::MoveLeft,
+            Token::MoveRight => SyntaxKind::MoveRight,
+            Token::Jump => SyntaxKind::Jump,
+            Token::Attack => SyntaxKind::Attack,
+            Token::Defend => SyntaxKind::Defend,
+            Token::If => SyntaxKind::If,
+            Token::Else => SyntaxKind::Else,
+            Token::While => SyntaxKind::While,
+            Token::For => SyntaxKind::For,
+            Token::Repeat => SyntaxKind::Repeat,
+            Token::Let => SyntaxKind::Let,
+            Token::Plus => SyntaxKind::Plus,
+            Token::Minus => SyntaxKind::Minus,
+            Token::Asterisk => SyntaxKind::Asterisk,
+            Token::Slash => SyntaxKind::Slash,
+            Token::LogicalAnd => SyntaxKind::LogicalAnd,
+            Token::LogicalOr => SyntaxKind::LogicalOr,
+            Token::LogicalNot => SyntaxKind::LogicalNot,
+            Token::EqualEqual => SyntaxKind::EqualEqual,
+            Token::NotEqual => SyntaxKind::NotEqual,
+            Token::Less => SyntaxKind::Less,
+            Token::LessEqual => SyntaxKind::LessEqual,
+            Token::Greater => SyntaxKind::Greater,
+            Token::GreaterEqual => SyntaxKind::GreaterEqual,
+            Token::Equal => SyntaxKind::Equal,
+            Token::PlusEqual => SyntaxKind::PlusEqual,
+            Token::LParen => SyntaxKind::LParen,
+            Token::RParen => SyntaxKind::RParen,
+            Token::LBrace => SyntaxKind::LBrace,
+            Token::RBrace => SyntaxKind::RBrace,
+            Token::Semicolon => SyntaxKind::Semicolon,
+            Token::Number(_) => SyntaxKind::Number,
+            Token::Identifier(_) => SyntaxKind::Identifier,
+            Token::Comment(_) => SyntaxKind::Comment,
+        }
+    }
+}
+
+impl From<SyntaxKind> for rowan::SyntaxKind {
+    fn from(kind: SyntaxKind) -> Self {
+        rowan::SyntaxKind(kind as u16)
+    }
+}
+
+/// Liga os tipos da linguagem ao rowan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QuestLanguage {}
+
+impl rowan::Language for QuestLanguage {
+    type Kind = SyntaxKind;
+
+    fn kind_from_raw(raw: rowan::SyntaxKind) -> SyntaxKind {
+        assert!(raw.0 <= SyntaxKind::Error as u16);
+        // SAFETY: `SyntaxKind` é `repr(u16)` e o valor foi verificado acima
+        unsafe { std::mem::transmute::<u16, SyntaxKind>(raw.0) }
+    }
+
+    fn kind_to_raw(kind: SyntaxKind) -> rowan::SyntaxKind {
+        kind.into()
+    }
+}
+
+pub type SyntaxNode = rowan::SyntaxNode<QuestLanguage>;
+pub type SyntaxToken = rowan::SyntaxToken<QuestLanguage>;
+pub type SyntaxElement = rowan::SyntaxElement<QuestLanguage>;
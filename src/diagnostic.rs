@@ -0,0 +1,283 @@
+use crate::error::{LexError, LexErrorKind, ParseError, QuestError, RuntimeError};
+use crate::span::{Position, Span};
+use crate::token::Token;
+use std::io::{self, IsTerminal, Write};
+
+/// Rótulo secundário apontando para outro trecho do código.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// Erro pronto para ser mostrado ao usuário, comum a todos os backends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+    /// Texto ao lado do sublinhado principal.
+    pub label: Option<String>,
+    /// Tokens (ou construções) que eram aceitos no ponto do erro.
+    pub expected: Vec<String>,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            message: message.into(),
+            span,
+            label: None,
+            expected: Vec::new(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Define o conjunto esperado, ordenado e sem repetições.
+    pub fn with_expected<I, S>(mut self, expected: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.expected = expected.into_iter().map(Into::into).collect();
+        self.expected.sort();
+        self.expected.dedup();
+        self
+    }
+
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// "expected `)`" ou "expected one of `)`, `+`", se houver conjunto esperado.
+    pub fn expected_text(&self) -> Option<String> {
+        match self.expected.as_slice() {
+            [] => None,
+            [only] => Some(format!("expected {}", only)),
+            many => Some(format!("expected one of {}", many.join(", "))),
+        }
+    }
+}
+
+/// Descrição de um token para mensagens: "`{`", "identifier `hero`"...
+pub fn describe_token(token: &Token) -> String {
+    match token {
+        Token::Identifier(_) => format!("identifier `{}`", token),
+        Token::Number(_) => format!("number `{}`", token),
+        Token::Comment(_) => "comment".to_string(),
+        _ => format!("`{}`", token),
+    }
+}
+
+impl From<&LexError> for Diagnostic {
+    fn from(error: &LexError) -> Self {
+        match error.kind {
+            LexErrorKind::UnexpectedCharacter => {
+                Diagnostic::new(format!("unexpected character `{}`", error.slice), error.span)
+                    .with_label("not part of any token")
+            }
+            LexErrorKind::IncompleteOperator => {
+                Diagnostic::new(format!("unexpected character `{}`", error.slice), error.span)
+                    .with_label(format!("did you mean `{}{}`?", error.slice, error.slice))
+                    .with_note("the logical operators are `&&` and `||`")
+            }
+            LexErrorKind::InvalidNumber => {
+                Diagnostic::new(format!("invalid number `{}`", error.slice), error.span)
+                    .with_label("too large")
+                    .with_note(format!("the largest supported number is {}", i64::MAX))
+            }
+        }
+    }
+}
+
+impl From<&Diagnostic> for Diagnostic {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        diagnostic.clone()
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(error: &ParseError) -> Self {
+        let expected = error.expected().iter().map(|e| e.to_string());
+        match error {
+            ParseError::UnexpectedToken { found, span, .. } => {
+                Diagnostic::new(format!("unexpected {}", describe_token(found)), *span)
+                    .with_expected(expected)
+            }
+            ParseError::UnexpectedEof { span, .. } => {
+                Diagnostic::new("unexpected end of input", *span).with_expected(expected)
+            }
+            ParseError::UnclosedDelimiter {
+                delimiter,
+                open,
+                span,
+                ..
+            } => Diagnostic::new(format!("unclosed delimiter {}", describe_token(delimiter)), *span)
+                .with_expected(expected)
+                .with_secondary(*open, "unclosed delimiter"),
+        }
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic {
+    fn from(error: &RuntimeError) -> Self {
+        let diagnostic = Diagnostic::new(error.to_string(), error.span());
+        match error {
+            RuntimeError::UnknownIdentifier { .. } => diagnostic.with_label("not defined"),
+            RuntimeError::StepLimitExceeded { .. } => diagnostic
+                .with_label("still running here")
+                .with_note("the program may be stuck in an infinite loop"),
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&QuestError> for Diagnostic {
+    fn from(error: &QuestError) -> Self {
+        match error {
+            QuestError::Lex(error) => error.into(),
+            QuestError::Parse(error) => error.into(),
+            QuestError::Runtime(error) => error.into(),
+        }
+    }
+}
+
+/// Cores ANSI usadas quando a saída é um terminal.
+struct Palette {
+    error: &'static str,
+    bold: &'static str,
+    gutter: &'static str,
+    secondary: &'static str,
+    reset: &'static str,
+}
+
+impl Palette {
+    fn new(color: bool) -> Self {
+        if color {
+            Palette {
+                error: "\x1b[1;31m",
+                bold: "\x1b[1m",
+                gutter: "\x1b[1;34m",
+                secondary: "\x1b[1;34m",
+                reset: "\x1b[0m",
+            }
+        } else {
+            Palette {
+                error: "",
+                bold: "",
+                gutter: "",
+                secondary: "",
+                reset: "",
+            }
+        }
+    }
+}
+
+/// Renderiza o diagnóstico com a linha do código, sublinhado, conjunto
+/// esperado, rótulos e notas. Com `color`, usa cores ANSI.
+pub fn render(diagnostic: &Diagnostic, source: &str, color: bool) -> String {
+    let p = Palette::new(color);
+    let start = Position::of(source, diagnostic.span.start);
+    let mut snippets = vec![(diagnostic.span, '^', p.error, primary_label(diagnostic))];
+    for label in &diagnostic.labels {
+        snippets.push((label.span, '-', p.secondary, Some(label.message.clone())));
+    }
+    // Mostra os trechos na ordem em que aparecem no código
+    snippets.sort_by_key(|(span, ..)| span.start);
+    let width = snippets
+        .iter()
+        .map(|(span, ..)| Position::of(source, span.start).line.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let pad = " ".repeat(width);
+
+    let mut out = format!(
+        "{}error{}{}: {}{}\n",
+        p.error, p.reset, p.bold, diagnostic.message, p.reset
+    );
+    out += &format!("{}{}-->{} {}\n", pad, p.gutter, p.reset, start);
+    out += &format!("{} {}|{}\n", pad, p.gutter, p.reset);
+
+    for (span, marker, marker_color, label) in snippets {
+        let position = Position::of(source, span.start);
+        let line = source.lines().nth(position.line - 1).unwrap_or("");
+        let prefix: String = line.chars().take(position.column - 1).collect();
+        // Sublinha até o fim do trecho ou da linha, com no mínimo um caractere
+        let span_len = span.end.saturating_sub(span.start);
+        let length = line[prefix.len()..]
+            .char_indices()
+            .take_while(|(i, _)| *i < span_len)
+            .count()
+            .max(1);
+        let indent: String = prefix
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        out += &format!(
+            "{}{:>width$} |{} {}\n",
+            p.gutter,
+            position.line,
+            p.reset,
+            line,
+            width = width
+        );
+        out += &format!(
+            "{} {}|{} {}{}{}",
+            pad,
+            p.gutter,
+            p.reset,
+            indent,
+            marker_color,
+            marker.to_string().repeat(length)
+        );
+        match label {
+            Some(label) => out += &format!(" {}{}\n", label, p.reset),
+            None => out += &format!("{}\n", p.reset),
+        }
+    }
+
+    if diagnostic.label.is_some() {
+        if let Some(expected) = diagnostic.expected_text() {
+            out += &format!("{} {}={} {}\n", pad, p.gutter, p.reset, expected);
+        }
+    }
+    for note in &diagnostic.notes {
+        out += &format!("{} {}={} {}note{}: {}\n", pad, p.gutter, p.reset, p.bold, p.reset, note);
+    }
+    out
+}
+
+/// O texto ao lado do `^`: o rótulo, ou então o conjunto esperado.
+fn primary_label(diagnostic: &Diagnostic) -> Option<String> {
+    diagnostic.label.clone().or_else(|| diagnostic.expected_text())
+}
+
+/// Escreve os erros (léxicos, sintáticos ou diagnósticos prontos) na saída
+/// de erro, com cores se ela for um terminal.
+pub fn emit<E>(errors: &[E], source: &str)
+where
+    for<'e> &'e E: Into<Diagnostic>,
+{
+    let stderr = io::stderr();
+    let color = stderr.is_terminal();
+    let mut out = stderr.lock();
+    for error in errors {
+        let _ = writeln!(out, "{}", render(&error.into(), source, color));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::ast::Command;
+use crate::diagnostic::describe_token;
+use crate::span::{Position, Span};
+use crate::token::Token;
+use std::fmt;
+
+/// Motivo de um erro léxico.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum LexErrorKind {
+    /// Caractere que não inicia nenhum token da linguagem.
+    #[default]
+    UnexpectedCharacter,
+    /// `&` ou `|` sozinhos: os operadores lógicos são `&&` e `||`.
+    IncompleteOperator,
+    /// Literal numérico inválido: um inteiro que não cabe em `i64`.
+    InvalidNumber,
+}
+
+/// Erro léxico com o trecho rejeitado e sua posição no código-fonte.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub slice: String,
+    pub span: Span,
+    pub position: Position,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            LexErrorKind::UnexpectedCharacter => {
+                write!(f, "unexpected character `{}` at {}", self.slice, self.position)
+            }
+            LexErrorKind::IncompleteOperator => write!(
+                f,
+                "unexpected character `{}` at {} (did you mean `{}{}`?)",
+                self.slice, self.position, self.slice, self.slice
+            ),
+            LexErrorKind::InvalidNumber => {
+                write!(f, "invalid number `{}` at {}: too large", self.slice, self.position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LexError {}
+
+/// O que o parser aceitaria no ponto de um erro sintático.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expected {
+    Token(Token),
+    EndOfInput,
+    /// Qualquer início de statement.
+    Statement,
+    /// Qualquer um dos comandos (`move_up`, `jump`, ...).
+    Command,
+    /// Qualquer início de expressão.
+    Expression,
+    /// Um nome de variável.
+    Identifier,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "{}", describe_token(token)),
+            Expected::EndOfInput => write!(f, "end of input"),
+            Expected::Statement => write!(f, "a statement"),
+            Expected::Command => write!(f, "a command"),
+            Expected::Expression => write!(f, "an expression"),
+            Expected::Identifier => write!(f, "an identifier"),
+        }
+    }
+}
+
+/// Erro sintático, comum a todos os parsers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParseError {
+    /// Token encontrado onde se esperava outra coisa.
+    UnexpectedToken {
+        expected: Vec<Expected>,
+        found: Token,
+        span: Span,
+        position: Position,
+    },
+    /// A entrada acabou antes do fim da construção; `span` é vazio e fica
+    /// logo após o último token.
+    UnexpectedEof {
+        expected: Vec<Expected>,
+        span: Span,
+        position: Position,
+    },
+    /// Delimitador aberto em `open` e nunca fechado.
+    UnclosedDelimiter {
+        delimiter: Token,
+        open: Span,
+        open_position: Position,
+        expected: Vec<Expected>,
+        span: Span,
+        position: Position,
+    },
+}
+
+impl ParseError {
+    /// Trecho em que o erro foi detectado.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::UnexpectedEof { span, .. }
+            | ParseError::UnclosedDelimiter { span, .. } => *span,
+        }
+    }
+
+    /// Linha e coluna do início de `span`.
+    pub fn position(&self) -> Position {
+        match self {
+            ParseError::UnexpectedToken { position, .. }
+            | ParseError::UnexpectedEof { position, .. }
+            | ParseError::UnclosedDelimiter { position, .. } => *position,
+        }
+    }
+
+    pub fn expected(&self) -> &[Expected] {
+        match self {
+            ParseError::UnexpectedToken { expected, .. }
+            | ParseError::UnexpectedEof { expected, .. }
+            | ParseError::UnclosedDelimiter { expected, .. } => expected,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedToken {
+                found, position, ..
+            } => write!(f, "unexpected {} at {}", describe_token(found), position)?,
+            ParseError::UnexpectedEof { position, .. } => {
+                write!(f, "unexpected end of input at {}", position)?
+            }
+            ParseError::UnclosedDelimiter {
+                delimiter,
+                open_position,
+                ..
+            } => write!(
+                f,
+                "unclosed delimiter {} opened at {}",
+                describe_token(delimiter),
+                open_position
+            )?,
+        }
+        let expected: Vec<String> = self.expected().iter().map(|e| e.to_string()).collect();
+        match expected.as_slice() {
+            [] => Ok(()),
+            [only] => write!(f, ", expected {}", only),
+            many => write!(f, ", expected one of {}", many.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Erro durante a execução de um programa pelo interpretador.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RuntimeError {
+    /// Identificador que o estado do jogo não conhece.
+    UnknownIdentifier { name: String, span: Span },
+    DivisionByZero { span: Span },
+    /// Resultado fora do intervalo de `i64`.
+    Overflow { span: Span },
+    /// O estado do jogo recusou o comando.
+    CommandFailed {
+        command: Command,
+        message: String,
+        span: Span,
+    },
+    /// O programa executou statements demais (provável laço infinito).
+    StepLimitExceeded { limit: usize, span: Span },
+    /// Nó `Error` da recuperação de erros: o programa não parseou.
+    InvalidProgram { span: Span },
+}
+
+impl RuntimeError {
+    /// Trecho do código onde o erro aconteceu.
+    pub fn span(&self) -> Span {
+        match self {
+            RuntimeError::UnknownIdentifier { span, .. }
+            | RuntimeError::DivisionByZero { span }
+            | RuntimeError::Overflow { span }
+            | RuntimeError::CommandFailed { span, .. }
+            | RuntimeError::StepLimitExceeded { span, .. }
+            | RuntimeError::InvalidProgram { span } => *span,
+        }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::UnknownIdentifier { name, .. } => {
+                write!(f, "unknown identifier `{}`", name)
+            }
+            RuntimeError::DivisionByZero { .. } => write!(f, "division by zero"),
+            RuntimeError::Overflow { .. } => write!(f, "arithmetic overflow"),
+            RuntimeError::CommandFailed {
+                command, message, ..
+            } => write!(f, "`{}` failed: {}", command, message),
+            RuntimeError::StepLimitExceeded { limit, .. } => {
+                write!(f, "step limit of {} exceeded", limit)
+            }
+            RuntimeError::InvalidProgram { .. } => write!(f, "cannot run a program with syntax errors"),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
+/// Qualquer erro da linguagem: léxico, sintático ou de execução.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QuestError {
+    Lex(LexError),
+    Parse(ParseError),
+    Runtime(RuntimeError),
+}
+
+impl fmt::Display for QuestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuestError::Lex(error) => write!(f, "{}", error),
+            QuestError::Parse(error) => write!(f, "{}", error),
+            QuestError::Runtime(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for QuestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            QuestError::Lex(error) => Some(error),
+            QuestError::Parse(error) => Some(error),
+            QuestError::Runtime(error) => Some(error),
+        }
+    }
+}
+
+impl From<LexError> for QuestError {
+    fn from(error: LexError) -> Self {
+        QuestError::Lex(error)
+    }
+}
+
+impl From<ParseError> for QuestError {
+    fn from(error: ParseError) -> Self {
+        QuestError::Parse(error)
+    }
+}
+
+impl From<RuntimeError> for QuestError {
+    fn from(error: RuntimeError) -> Self {
+        QuestError::Runtime(error)
+    }
+}
@@ -0,0 +1,262 @@
+use crate::ast::*;
+use crate::span::Span;
+use crate::trivia::Comment;
+
+/// Recuo de cada nível de bloco.
+const INDENT: &str = "    ";
+
+/// Gera o código canônico do programa: quatro espaços por nível, `{` na linha
+/// do statement, um statement por linha e no máximo uma linha em branco entre
+/// eles (onde o original tinha alguma). Corpos sem chaves ficam na linha do
+/// `if`, `while`, `repeat` ou `for`, como `if (hero) jump else defend`. Os comentários voltam antes do
+/// statement seguinte, ou no fim da linha se no original estavam lá.
+///
+/// `source` é o código de onde vieram `program` e `comments`; nós `Error` da
+/// recuperação de erros não são impressos.
+pub fn format_program(program: &[Statement], comments: &[Comment], source: &str) -> String {
+    let mut printer = Printer {
+        source,
+        comments,
+        next_comment: 0,
+        out: String::new(),
+        depth: 0,
+        last_end: 0,
+        at_block_start: true,
+        block_end: source.len(),
+    };
+    printer.statements(program);
+    printer.comments_before(source.len());
+    printer.out
+}
+
+/// Formata o código com o lexer e o parser manuais. Falha se houver erros.
+#[cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+pub fn format(source: &str) -> Result<String, Vec<crate::error::QuestError>> {
+    use crate::error::QuestError;
+
+    let tokens = crate::lexer_manual::lexer_impl::lex(source)
+        .map_err(|errors| errors.into_iter().map(QuestError::from).collect::<Vec<_>>())?;
+    let (tokens, comments) = crate::trivia::split_comments(tokens);
+    let program = crate::parser_manual::parser_impl::parse(tokens)
+        .map_err(|errors| errors.into_iter().map(QuestError::from).collect::<Vec<_>>())?;
+    Ok(format_program(&program, &comments, source))
+}
+
+/// Diz se o código já está no formato canônico.
+#[cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+pub fn is_formatted(source: &str) -> Result<bool, Vec<crate::error::QuestError>> {
+    Ok(format(source)? == source)
+}
+
+struct Printer<'a> {
+    source: &'a str,
+    comments: &'a [Comment],
+    /// Primeiro comentário ainda não impresso.
+    next_comment: usize,
+    out: String,
+    depth: usize,
+    /// Fim, no código original, do último item impresso.
+    last_end: usize,
+    /// Nada foi impresso ainda no bloco atual (nem linha em branco).
+    at_block_start: bool,
+    /// Fim, no original, do bloco sendo impresso.
+    block_end: usize,
+}
+
+impl Printer<'_> {
+    fn statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            if statement.kind == StatementKind::Error {
+                continue;
+            }
+            self.comments_before(statement.span.start);
+            self.begin_line(statement.span.start);
+            self.statement(statement);
+            self.last_end = statement.span.end;
+            self.trailing_comment();
+            self.out.push('\n');
+        }
+    }
+
+    /// Imprime, cada um na sua linha, os comentários que começam antes de `offset`.
+    fn comments_before(&mut self, offset: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.span.start >= offset {
+                break;
+            }
+            self.begin_line(comment.span.start);
+            self.out.push_str(comment.text.trim_end());
+            self.out.push('\n');
+            self.last_end = comment.span.end;
+            self.next_comment += 1;
+        }
+    }
+
+    /// Comentário que, no original, vinha logo depois do último item, na
+    /// mesma linha e sem outro statement entre os dois (e ainda dentro do
+    /// mesmo bloco).
+    fn trailing_comment(&mut self) {
+        if let Some(comment) = self
+            .comments
+            .get(self.next_comment)
+            .filter(|c| c.span.start < self.block_end)
+        {
+            let between = self.source.get(self.last_end..comment.span.start).unwrap_or("\n");
+            if !between.contains('\n') && between.trim().is_empty() {
+                self.out.push(' ');
+                self.out.push_str(comment.text.trim_end());
+                self.last_end = comment.span.end;
+                self.next_comment += 1;
+            }
+        }
+    }
+
+    /// Recua a nova linha, mantendo uma linha em branco se o original tinha
+    /// uma logo antes de `start`. O texto anterior pode ter pedaços já
+    /// impressos de um statement com comentário no meio (um `else {`, um `)`),
+    /// e as quebras de linha deles não contam.
+    fn begin_line(&mut self, start: usize) {
+        let between = self.source.get(self.last_end..start).unwrap_or("");
+        let gap = &between[between.trim_end().len()..];
+        if !self.at_block_start && gap.matches('\n').count() >= 2 {
+            self.out.push('\n');
+        }
+        self.at_block_start = false;
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match &statement.kind {
+            StatementKind::Command(command) => self.out.push_str(&command.to_string()),
+            StatementKind::Block(statements) => self.block(statements, statement.span),
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.out.push_str(&format!("if ({}) ", expression(condition)));
+                self.statement(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.out.push_str(" else ");
+                    self.statement(else_branch);
+                }
+            }
+            StatementKind::While { condition, body } => {
+                self.out.push_str(&format!("while ({}) ", expression(condition)));
+                self.statement(body);
+            }
+            StatementKind::Repeat { count, body } => {
+                self.out.push_str(&format!("repeat ({}) ", expression(count)));
+                self.statement(body);
+            }
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => {
+                self.out.push_str(&format!(
+                    "for ({}; {}; {}) ",
+                    simple_statement(init),
+                    expression(condition),
+                    simple_statement(update)
+                ));
+                self.statement(body);
+            }
+            StatementKind::Let { .. } | StatementKind::Assign { .. } => {
+                self.out.push_str(&simple_statement(statement));
+                self.out.push(';');
+            }
+            StatementKind::Error => {}
+        }
+    }
+
+    /// Bloco com o conteúdo recuado; `{}` se não tiver statements nem comentários.
+    fn block(&mut self, statements: &[Statement], span: Span) {
+        let has_comments = self
+            .comments
+            .get(self.next_comment)
+            .is_some_and(|c| c.span.start < span.end);
+        if statements.is_empty() && !has_comments {
+            self.out.push_str("{}");
+            return;
+        }
+        self.out.push_str("{\n");
+        self.depth += 1;
+        self.at_block_start = true;
+        let outer_end = std::mem::replace(&mut self.block_end, span.end);
+        self.statements(statements);
+        self.comments_before(span.end);
+        self.block_end = outer_end;
+        self.depth -= 1;
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+        self.out.push('}');
+    }
+}
+
+/// Texto de um `let` ou atribuição, sem o `;`, como no cabeçalho do for.
+fn simple_statement(statement: &Statement) -> String {
+    match &statement.kind {
+        StatementKind::Let { name, value } => format!("let {} = {}", name, expression(value)),
+        StatementKind::Assign { name, op, value } => {
+            format!("{} {} {}", name, op, expression(value))
+        }
+        _ => String::new(),
+    }
+}
+
+/// Texto canônico da expressão, só com os parênteses necessários.
+pub fn expression(expr: &Expression) -> String {
+    match &expr.kind {
+        ExpressionKind::Identifier(name) => name.clone(),
+        ExpressionKind::Number(n) => n.to_string(),
+        ExpressionKind::Unary { op, operand } => {
+            // Um `-` dentro de outro também vai entre parênteses: `-(-1)`, não `--1`
+            let operand_text = match (op, &operand.kind) {
+                (_, ExpressionKind::Binary { .. })
+                | (UnaryOp::Negate, ExpressionKind::Unary { op: UnaryOp::Negate, .. }) => {
+                    format!("({})", expression(operand))
+                }
+                _ => expression(operand),
+            };
+            format!("{}{}", op, operand_text)
+        }
+        ExpressionKind::Binary { left, op, right } => {
+            let precedence = op.precedence();
+            // Comparações não associam: mesmo à esquerda, outra comparação
+            // precisa de parênteses
+            let left_needs = match binary_precedence(left) {
+                Some(p) if p == 3 && precedence == 3 => true,
+                Some(p) => p < precedence,
+                None => false,
+            };
+            let right_needs = binary_precedence(right).is_some_and(|p| p <= precedence);
+            format!(
+                "{} {} {}",
+                parenthesize(left, left_needs),
+                op,
+                parenthesize(right, right_needs)
+            )
+        }
+        ExpressionKind::Error => String::new(),
+    }
+}
+
+fn binary_precedence(expr: &Expression) -> Option<u8> {
+    match &expr.kind {
+        ExpressionKind::Binary { op, .. } => Some(op.precedence()),
+        _ => None,
+    }
+}
+
+fn parenthesize(expr: &Expression, needed: bool) -> String {
+    if needed {
+        format!("({})", expression(expr))
+    } else {
+        expression(expr)
+    }
+}
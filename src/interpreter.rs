@@ -0,0 +1,235 @@
+use crate::ast::*;
+use crate::error::RuntimeError;
+use crate::span::Span;
+use std::collections::HashMap;
+
+/// Limite padrão de statements executados, contra laços infinitos.
+pub const DEFAULT_STEP_LIMIT: usize = 1_000_000;
+
+/// Estado do jogo sobre o qual o programa roda.
+pub trait GameState {
+    /// Chamado a cada comando executado. Um `Err` interrompe o programa com
+    /// a mensagem dada (por exemplo, o herói caiu num buraco).
+    fn execute(&mut self, command: Command) -> Result<(), String>;
+
+    /// Valor de um identificador usado numa expressão, se ele existir.
+    fn read(&mut self, name: &str) -> Option<i64>;
+}
+
+/// Interpretador que percorre a árvore diretamente.
+///
+/// Os valores são inteiros; nas condições, zero é falso e qualquer outro
+/// valor é verdadeiro. Comparações, `!`, `&&` e `||` resultam em 0 ou 1, e
+/// `&&`/`||` só avaliam o lado direito se precisarem.
+///
+/// Cada bloco (e cada for, a partir do `init`) abre um escopo de variáveis
+/// que some ao fim dele. Um identificador é procurado primeiro entre as
+/// variáveis, do escopo mais interno para fora, e só depois no estado.
+pub struct Interpreter<'a, S: GameState> {
+    state: &'a mut S,
+    steps: usize,
+    step_limit: usize,
+    /// Escopos abertos; o primeiro é o do programa.
+    scopes: Vec<HashMap<String, i64>>,
+}
+
+impl<'a, S: GameState> Interpreter<'a, S> {
+    pub fn new(state: &'a mut S) -> Self {
+        Interpreter {
+            state,
+            steps: 0,
+            step_limit: DEFAULT_STEP_LIMIT,
+            scopes: vec![HashMap::new()],
+        }
+    }
+
+    /// Troca o número máximo de statements executados.
+    pub fn with_step_limit(mut self, limit: usize) -> Self {
+        self.step_limit = limit;
+        self
+    }
+
+    /// Statements executados até agora.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Valor atual da variável visível com esse nome.
+    pub fn variable(&self, name: &str) -> Option<i64> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).copied())
+    }
+
+    pub fn run(&mut self, program: &[Statement]) -> Result<(), RuntimeError> {
+        for statement in program {
+            self.execute(statement)?;
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, statement: &Statement) -> Result<(), RuntimeError> {
+        self.step(statement.span)?;
+        match &statement.kind {
+            StatementKind::Command(command) => {
+                self.state
+                    .execute(*command)
+                    .map_err(|message| RuntimeError::CommandFailed {
+                        command: *command,
+                        message,
+                        span: statement.span,
+                    })
+            }
+            StatementKind::Block(statements) => self.scoped(|interpreter| interpreter.run(statements)),
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if self.evaluate(condition)? != 0 {
+                    self.execute(then_branch)
+                } else if let Some(else_branch) = else_branch {
+                    self.execute(else_branch)
+                } else {
+                    Ok(())
+                }
+            }
+            StatementKind::While { condition, body } => {
+                while self.evaluate(condition)? != 0 {
+                    self.execute(body)?;
+                }
+                Ok(())
+            }
+            StatementKind::For {
+                init,
+                condition,
+                update,
+                body,
+            } => self.scoped(|interpreter| {
+                interpreter.execute(init)?;
+                while interpreter.evaluate(condition)? != 0 {
+                    interpreter.execute(body)?;
+                    interpreter.execute(update)?;
+                }
+                Ok(())
+            }),
+            StatementKind::Repeat { count, body } => {
+                // A contagem é lida uma vez só, antes de começar
+                let count = self.evaluate(count)?;
+                for _ in 0..count.max(0) {
+                    self.execute(body)?;
+                }
+                Ok(())
+            }
+            StatementKind::Let { name, value } => {
+                let value = self.evaluate(value)?;
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.insert(name.clone(), value);
+                }
+                Ok(())
+            }
+            StatementKind::Assign { name, op, value } => {
+                let value = self.evaluate(value)?;
+                let Some(variable) = self
+                    .scopes
+                    .iter_mut()
+                    .rev()
+                    .find_map(|scope| scope.get_mut(name))
+                else {
+                    return Err(RuntimeError::UnknownIdentifier {
+                        name: name.clone(),
+                        span: statement.span,
+                    });
+                };
+                *variable = match op {
+                    AssignOp::Set => value,
+                    AssignOp::Add => variable
+                        .checked_add(value)
+                        .ok_or(RuntimeError::Overflow {
+                            span: statement.span,
+                        })?,
+                };
+                Ok(())
+            }
+            StatementKind::Error => Err(RuntimeError::InvalidProgram {
+                span: statement.span,
+            }),
+        }
+    }
+
+    /// Roda `f` num escopo novo, descartado ao fim mesmo se houver erro.
+    fn scoped(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<(), RuntimeError>,
+    ) -> Result<(), RuntimeError> {
+        self.scopes.push(HashMap::new());
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+
+    /// Conta um passo, falhando se o limite foi atingido.
+    fn step(&mut self, span: Span) -> Result<(), RuntimeError> {
+        if self.steps >= self.step_limit {
+            return Err(RuntimeError::StepLimitExceeded {
+                limit: self.step_limit,
+                span,
+            });
+        }
+        self.steps += 1;
+        Ok(())
+    }
+
+    pub fn evaluate(&mut self, expr: &Expression) -> Result<i64, RuntimeError> {
+        let overflow = || RuntimeError::Overflow { span: expr.span };
+        match &expr.kind {
+            ExpressionKind::Number(n) => Ok(*n),
+            ExpressionKind::Identifier(name) => {
+                self.variable(name)
+                    .or_else(|| self.state.read(name))
+                    .ok_or_else(|| RuntimeError::UnknownIdentifier {
+                        name: name.clone(),
+                        span: expr.span,
+                    })
+            }
+            ExpressionKind::Unary { op, operand } => {
+                let value = self.evaluate(operand)?;
+                match op {
+                    UnaryOp::Not => Ok((value == 0) as i64),
+                    UnaryOp::Negate => value.checked_neg().ok_or_else(overflow),
+                }
+            }
+            ExpressionKind::Binary { left, op, right } => {
+                let left = self.evaluate(left)?;
+                // Curto-circuito: o lado direito só é avaliado se decidir
+                match op {
+                    BinaryOp::And if left == 0 => return Ok(0),
+                    BinaryOp::Or if left != 0 => return Ok(1),
+                    _ => {}
+                }
+                let right_span = right.span;
+                let right = self.evaluate(right)?;
+                match op {
+                    BinaryOp::Plus => left.checked_add(right).ok_or_else(overflow),
+                    BinaryOp::Minus => left.checked_sub(right).ok_or_else(overflow),
+                    BinaryOp::Multiply => left.checked_mul(right).ok_or_else(overflow),
+                    BinaryOp::Divide if right == 0 => {
+                        Err(RuntimeError::DivisionByZero { span: right_span })
+                    }
+                    BinaryOp::Divide => left.checked_div(right).ok_or_else(overflow),
+                    BinaryOp::Equal => Ok((left == right) as i64),
+                    BinaryOp::NotEqual => Ok((left != right) as i64),
+                    BinaryOp::Less => Ok((left < right) as i64),
+                    BinaryOp::LessEqual => Ok((left <= right) as i64),
+                    BinaryOp::Greater => Ok((left > right) as i64),
+                    BinaryOp::GreaterEqual => Ok((left >= right) as i64),
+                    BinaryOp::And | BinaryOp::Or => Ok((right != 0) as i64),
+                }
+            }
+            ExpressionKind::Error => Err(RuntimeError::InvalidProgram { span: expr.span }),
+        }
+    }
+}
+
+/// Executa o programa sobre o estado, com o limite de passos padrão.
+pub fn run<S: GameState>(program: &[Statement], state: &mut S) -> Result<(), RuntimeError> {
+    Interpreter::new(state).run(program)
+}
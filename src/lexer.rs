@@ -1,2 +1 @@
-pub mod token;
 pub mod lexer_impl;
\ No newline at end of file
@@ -1,8 +1,15 @@
-use crate::lexer::token::Token;
+use crate::error::LexError;
+use crate::span::{Position, Span, Spanned};
+use crate::token::{collect_tokens, Token};
 use logos::Logos;
 
 pub struct Lexer<'a> {
     inner: logos::Lexer<'a, Token>,
+    /// Posição do último byte já contabilizado em `line`/`column`.
+    scanned: usize,
+    line: usize,
+    /// Coluna de `scanned`.
+    column: usize,
 }
 
 impl<'a> Lexer<'a> {
@@ -10,23 +17,51 @@ impl<'a> Lexer<'a> {
     pub fn new(input: &'a str) -> Self {
         Lexer {
             inner: Token::lexer(input),
+            scanned: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// Calcula linha e coluna de `offset`, avançando a partir do último ponto
+    /// visto: só o trecho novo é percorrido.
+    fn position_of(&mut self, offset: usize) -> Position {
+        let skipped = &self.inner.source()[self.scanned..offset];
+        match skipped.rfind('\n') {
+            Some(newline) => {
+                self.line += skipped.matches('\n').count();
+                self.column = skipped[newline + 1..].chars().count() + 1;
+            }
+            None => self.column += skipped.chars().count(),
+        }
+        self.scanned = offset;
+        Position {
+            line: self.line,
+            column: self.column,
         }
     }
 }
 
 impl<'a> Iterator for Lexer<'a> {
-    type Item = Token;
+    type Item = Result<Spanned<Token>, LexError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|result| {
-            match result {
-                Ok(token) => token,
-                Err(_) => Token::Error,
-            }
+        let result = self.inner.next()?;
+        let span = Span::from(self.inner.span());
+        let position = self.position_of(span.start);
+        Some(match result {
+            Ok(token) => Ok(Spanned::new(token, span, position)),
+            Err(kind) => Err(LexError {
+                kind,
+                slice: self.inner.slice().to_string(),
+                span,
+                position,
+            }),
         })
     }
 }
 
-pub fn lex(input: &str) -> Vec<Token> {
-    Lexer::new(input).collect()
-}
\ No newline at end of file
+/// Tokeniza a entrada inteira, reunindo todos os erros léxicos encontrados.
+pub fn lex(input: &str) -> Result<Vec<Spanned<Token>>, Vec<LexError>> {
+    collect_tokens(Lexer::new(input))
+}
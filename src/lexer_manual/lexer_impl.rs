@@ -1,8 +1,14 @@
-use crate::lexer_manual::token::Token;
+use crate::error::{LexError, LexErrorKind};
+use crate::span::{Position, Span, Spanned};
+use crate::token::{collect_tokens, Token};
 
 pub struct Lexer {
     input: Vec<char>,
     pos: usize,
+    /// Deslocamento em bytes correspondente a `pos`.
+    offset: usize,
+    line: usize,
+    column: usize,
 }
 
 impl Lexer {
@@ -11,40 +17,64 @@ impl Lexer {
         Lexer {
             input: input.chars().collect(),
             pos: 0,
+            offset: 0,
+            line: 1,
+            column: 1,
         }
     }
 
-    /// Retorna o próximo token encontrado na entrada.
-    pub fn next_token(&mut self) -> Token {
+    /// Retorna o próximo token encontrado na entrada, junto com sua posição,
+    /// ou `None` quando a entrada acabou.
+    pub fn next_token(&mut self) -> Option<Result<Spanned<Token>, LexError>> {
         self.skip_whitespace();
 
         if self.pos >= self.input.len() {
-            return Token::EOF;
+            return None;
         }
 
+        let start = self.offset;
+        let start_pos = self.pos;
+        let position = Position {
+            line: self.line,
+            column: self.column,
+        };
+        let result = self.scan_token();
+        let span = Span::new(start, self.offset);
+        Some(match result {
+            Ok(token) => Ok(Spanned::new(token, span, position)),
+            Err(kind) => Err(LexError {
+                kind,
+                slice: self.input[start_pos..self.pos].iter().collect(),
+                span,
+                position,
+            }),
+        })
+    }
+
+    fn scan_token(&mut self) -> Result<Token, LexErrorKind> {
         let current = self.input[self.pos];
 
-        // Tratamento de comentários (// comentário)
+        // Tratamento de comentários (// comentário), guardados com as barras, como no Logos
         if current == '/' && self.peek() == Some('/') {
-            self.pos += 2; // pula os dois caracteres '/'
             let start = self.pos;
             while self.pos < self.input.len() && self.input[self.pos] != '\n' {
-                self.pos += 1;
+                self.advance();
             }
             let comment: String = self.input[start..self.pos].iter().collect();
-            return Token::Comment(comment);
+            return Ok(Token::Comment(comment));
         }
 
         // Números inteiros
         if current.is_ascii_digit() {
             let start = self.pos;
             while self.pos < self.input.len() && self.input[self.pos].is_ascii_digit() {
-                self.pos += 1;
+                self.advance();
             }
             let num_str: String = self.input[start..self.pos].iter().collect();
-            if let Ok(num) = num_str.parse::<i64>() {
-                return Token::Number(num);
-            }
+            return num_str
+                .parse::<i64>()
+                .map(Token::Number)
+                .map_err(|_| LexErrorKind::InvalidNumber);
         }
 
         // Identificadores e palavras-chave
@@ -53,10 +83,10 @@ impl Lexer {
             while self.pos < self.input.len()
                 && (self.input[self.pos].is_ascii_alphanumeric() || self.input[self.pos] == '_')
             {
-                self.pos += 1;
+                self.advance();
             }
             let word: String = self.input[start..self.pos].iter().collect();
-            return match word.as_str() {
+            return Ok(match word.as_str() {
                 "move_up" => Token::MoveUp,
                 "move_down" => Token::MoveDown,
                 "move_left" => Token::MoveLeft,
@@ -68,80 +98,144 @@ impl Lexer {
                 "else" => Token::Else,
                 "while" => Token::While,
                 "for" => Token::For,
+                "repeat" => Token::Repeat,
+                "let" => Token::Let,
                 _ => Token::Identifier(word),
-            };
+            });
         }
 
         // Operadores e símbolos especiais
-        match current {
+        let token = match current {
             '+' => {
-                self.pos += 1;
-                Token::Plus
+                if self.peek() == Some('=') {
+                    self.advance_n(2);
+                    Token::PlusEqual
+                } else {
+                    self.advance();
+                    Token::Plus
+                }
             }
             '-' => {
-                self.pos += 1;
+                self.advance();
                 Token::Minus
             }
             '*' => {
-                self.pos += 1;
+                self.advance();
                 Token::Asterisk
             }
             '/' => {
-                self.pos += 1;
+                self.advance();
                 Token::Slash
             }
             '(' => {
-                self.pos += 1;
+                self.advance();
                 Token::LParen
             }
             ')' => {
-                self.pos += 1;
+                self.advance();
                 Token::RParen
             }
             '{' => {
-                self.pos += 1;
+                self.advance();
                 Token::LBrace
             }
             '}' => {
-                self.pos += 1;
+                self.advance();
                 Token::RBrace
             }
+            ';' => {
+                self.advance();
+                Token::Semicolon
+            }
             '!' => {
-                self.pos += 1;
-                Token::LogicalNot
+                if self.peek() == Some('=') {
+                    self.advance_n(2);
+                    Token::NotEqual
+                } else {
+                    self.advance();
+                    Token::LogicalNot
+                }
+            }
+            '=' => {
+                if self.peek() == Some('=') {
+                    self.advance_n(2);
+                    Token::EqualEqual
+                } else {
+                    self.advance();
+                    Token::Equal
+                }
+            }
+            '<' => {
+                if self.peek() == Some('=') {
+                    self.advance_n(2);
+                    Token::LessEqual
+                } else {
+                    self.advance();
+                    Token::Less
+                }
+            }
+            '>' => {
+                if self.peek() == Some('=') {
+                    self.advance_n(2);
+                    Token::GreaterEqual
+                } else {
+                    self.advance();
+                    Token::Greater
+                }
             }
             '&' => {
                 if self.peek() == Some('&') {
-                    self.pos += 2;
+                    self.advance_n(2);
                     Token::LogicalAnd
                 } else {
-                    self.pos += 1;
-                    // Caso simples: se & aparecer sozinho, tratar como LogicalAnd
-                    Token::LogicalAnd
+                    // `&` sozinho não é operador
+                    self.advance();
+                    return Err(LexErrorKind::IncompleteOperator);
                 }
             }
             '|' => {
                 if self.peek() == Some('|') {
-                    self.pos += 2;
+                    self.advance_n(2);
                     Token::LogicalOr
                 } else {
-                    self.pos += 1;
-                    // Similarmente, caso de uso isolado de '|'
-                    Token::LogicalOr
+                    self.advance();
+                    return Err(LexErrorKind::IncompleteOperator);
                 }
             }
             _ => {
-                // Se o caractere não for reconhecido, pula-o e retorna um identificador com o char
-                self.pos += 1;
-                Token::Identifier(current.to_string())
+                // Se o caractere não for reconhecido, pula-o e reporta o erro
+                self.advance();
+                return Err(LexErrorKind::UnexpectedCharacter);
             }
+        };
+        Ok(token)
+    }
+
+    /// Avança um caractere, mantendo deslocamento, linha e coluna atualizados.
+    fn advance(&mut self) {
+        let current = self.input[self.pos];
+        self.pos += 1;
+        self.offset += current.len_utf8();
+        if current == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+    }
+
+    fn advance_n(&mut self, count: usize) {
+        for _ in 0..count {
+            self.advance();
         }
     }
 
-    /// Pula espaços em branco e outros separadores
+    /// Pula espaços em branco e outros separadores (os mesmos ignorados pelo Logos)
     fn skip_whitespace(&mut self) {
-        while self.pos < self.input.len() && self.input[self.pos].is_whitespace() {
-            self.pos += 1;
+        while self.pos < self.input.len()
+            && matches!(self.input[self.pos], ' ' | '\t' | '\r' | '\n' | '\x0C')
+        {
+            self.advance();
         }
     }
 
@@ -155,28 +249,15 @@ impl Lexer {
     }
 }
 
-pub fn lex(input: &str) -> Vec<Token> {
-    // Exemplo simples: para cada palavra, mapeia para um token se possível.
-    input.split_whitespace().map(|s| {
-        match s {
-            "if"    => Token::If,
-            "else"  => Token::Else,
-            "while" => Token::While,
-            "for"   => Token::For,
-            "move_up"    => Token::MoveUp,
-            "move_down"  => Token::MoveDown,
-            "move_left"  => Token::MoveLeft,
-            "move_right" => Token::MoveRight,
-            "jump"       => Token::Jump,
-            "attack"     => Token::Attack,
-            "defend"     => Token::Defend,
-            // Se for um número, por exemplo
-            _ if s.chars().all(|c| c.is_digit(10)) => {
-                // Aqui, assumindo que o parse não falha
-                Token::Number(s.parse::<i64>().unwrap())
-            }
-            // Para identificadores (hero, enemy, etc.) ou outros casos
-            _ => Token::Identifier(s.to_string()),
-        }
-    }).collect()
-}
\ No newline at end of file
+impl Iterator for Lexer {
+    type Item = Result<Spanned<Token>, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+}
+
+/// Tokeniza a entrada inteira, reunindo todos os erros léxicos encontrados.
+pub fn lex(input: &str) -> Result<Vec<Spanned<Token>>, Vec<LexError>> {
+    collect_tokens(Lexer::new(input))
+}
@@ -0,0 +1,24 @@
+pub mod error;
+pub mod diagnostic;
+pub mod ast; //ÁRVORE SINTÁTICA ABSTRATA ou ABSTRACT SYNTATIC TREE
+pub mod span;
+pub mod token;
+pub mod trivia;
+pub mod backend;
+pub mod formatter;
+pub mod interpreter;
+pub mod world;
+pub mod sensor;
+
+#[cfg(feature = "logos-lexer")]
+pub mod lexer;
+#[cfg(feature = "manual-lexer")]
+pub mod lexer_manual;
+#[cfg(feature = "chumsky-parser")]
+pub mod parser;
+#[cfg(feature = "manual-parser")]
+pub mod parser_manual;
+#[cfg(feature = "nom-parser")]
+pub mod parser_nom;
+#[cfg(feature = "cst")]
+pub mod cst;
@@ -1,8 +1,6 @@
-mod lexer;
-mod parser;
-
-use lexer::lexer_impl::lex;
-use parser::parser_impl::parse_tokens;
+use quest_lang::diagnostic;
+use quest_lang::lexer::lexer_impl::lex;
+use quest_lang::parser::parser_impl::parse_tokens_recovery;
 
 fn main() {
     // Exemplo de código em QuestLang
@@ -12,23 +10,28 @@ fn main() {
     "#;
 
     // Executa o lexer (Logos) para gerar um vetor de tokens
-    let tokens = lex(code);
+    let tokens = match lex(code) {
+        Ok(tokens) => tokens,
+        Err(errors) => {
+            diagnostic::emit(&errors, code);
+            return;
+        }
+    };
     println!("Tokens gerados:");
     for token in &tokens {
         println!("{:?}", token);
     }
 
-    // Executa o parser (Chumsky) usando os tokens gerados
-    match parse_tokens(tokens) {
-        Ok(ast) => {
+    // Executa o parser (Chumsky) usando os tokens gerados, recuperando-se dos
+    // erros para mostrar todos eles e a AST parcial
+    let (ast, errors) = parse_tokens_recovery(tokens);
+    if let Some(ast) = ast {
+        if errors.is_empty() {
             println!("\nAST gerada com sucesso:");
-            println!("{:#?}", ast);
-        }
-        Err(errors) => {
-            println!("\nErros de parsing:");
-            for error in errors {
-                println!("{:?}", error);
-            }
+        } else {
+            println!("\nAST parcial:");
         }
+        println!("{:#?}", ast);
     }
+    diagnostic::emit(&errors, code);
 }
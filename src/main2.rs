@@ -1,31 +1,10 @@
 use std::io::{self, Write};
 
-// Importa explicitamente cada módulo (eles estão no mesmo crate)
-mod lexer_manual;
-mod lexer;
-mod parser_manual;
-mod parser_nom;
-mod parser;
-
-// Re-exporta as funções (opcional) ou chama diretamente com nome do módulo
-use crate::lexer_manual::lexer_impl::lex as manual_lex;
-use crate::lexer::lexer_impl::lex as logos_lex;
-use crate::parser_manual::parser_impl::parse as manual_parse;
-// use crate::parser_nom::parser_impl::parse as nom_parse;
-use crate::parser::parser_impl::parse_tokens as chumsky_parse;
-
-#[derive(Debug)]
-enum LexChoice {
-    Manual,
-    Logos,
-}
-
-#[derive(Debug)]
-enum ParseChoice {
-    Manual,
-    Nom,
-    Chumsky,
-}
+// Os backends disponíveis dependem das features habilitadas na compilação
+use quest_lang::backend::{self, LexerBackend, ParserBackend};
+use quest_lang::diagnostic;
+use quest_lang::span::Position;
+use quest_lang::trivia;
 
 fn main() {
     let code = r#"
@@ -43,8 +22,8 @@ fn main() {
         move_right 
     }
     
-    for (hero; enemy; 3) { 
-        // Loop: inicializa com 'hero', condição 'enemy' e atualização '3'
+    for (let i = 0; i < enemy; i += 1) { 
+        // Loop: conta de 0 até 'enemy', de um em um
         move_up 
         if (treasure) { 
             move_right 
@@ -55,105 +34,83 @@ fn main() {
     }
     "#;
 
-    let code = r#"
-        if (hero + 10 { 
-            move_up 
-        } else { 
-            jump 
-        }
-        "#;
-
-    
+    let lexers = backend::lexers();
+    let parsers = backend::parsers();
+    if lexers.is_empty() || parsers.is_empty() {
+        eprintln!("Compile com ao menos um lexer e um parser habilitados.");
+        return;
+    }
 
-    let lex_choice = choose_lex();
-    let parse_choice = choose_parser();
+    let lexer = choose_lex(lexers);
+    let parser = choose_parser(parsers);
 
     // 1) Análise Léxica
-    let tokens = match lex_choice {
-        LexChoice::Manual => {
-            println!("\n[INFO] Usando lexer manual...");
-            manual_lex(code)
-        }
-        LexChoice::Logos => {
-            println!("\n[INFO] Usando lexer Logos...");
-            logos_lex(code)
+    println!("\n[INFO] Usando lexer {}...", lexer.name);
+    let tokens = match (lexer.lex)(code) {
+        Ok(tokens) => tokens,
+        Err(errors) => {
+            diagnostic::emit(&errors, code);
+            return;
         }
     };
 
     println!("\nTokens gerados:\n{:?}", tokens);
+    let (_, comments) = trivia::split_comments(tokens.clone());
 
     // 2) Análise Sintática
-    match parse_choice {
-        ParseChoice::Manual => {
-            println!("\n[INFO] Usando parser manual...");
-            match manual_parse(tokens) {
-                Ok(ast) => println!("AST gerada com sucesso!\n{:#?}", ast),
-                Err(e) => eprintln!("Erro no parser manual: {:?}", e),
-            }
-        }
-        ParseChoice::Nom => {
-            println!("\n[INFO] Usando parser com Nom...");
-            /* 
-            match nom_parse(tokens) {
-                Ok((rest, ast)) => {
-                    println!("AST gerada com sucesso!\n{:#?}", ast);
-                    println!("Restante não parseado: {:?}", rest);
-                }
-                Err(e) => eprintln!("Erro no parser Nom: {:?}", e),
-            }
-            */
-        }
-        ParseChoice::Chumsky => {
-            println!("\n[INFO] Usando parser com Chumsky...");
-            match chumsky_parse(tokens) {
-                Ok(ast) => println!("AST gerada com sucesso!\n{:#?}", ast),
-                Err(errors) => {
-                    eprintln!("Erros de parsing:");
-                    for err in errors {
-                        eprintln!("{:?}", err);
-                    }
+    println!("\n[INFO] Usando parser {}...", parser.name);
+    match (parser.parse)(tokens) {
+        Ok(ast) => {
+            println!("AST gerada com sucesso!\n{:#?}", ast);
+            // Os comentários ficam fora da AST, mas cada um tem o seu statement
+            println!("\nComentários:");
+            for comment in &comments {
+                match trivia::nearest_statement(&ast, comment) {
+                    Some(statement) => println!(
+                        "{} {} -> statement em {}",
+                        comment.position,
+                        comment.text,
+                        Position::of(code, statement.span.start)
+                    ),
+                    None => println!("{} {}", comment.position, comment.text),
                 }
             }
         }
+        Err(errors) => diagnostic::emit(&errors, code),
     }
 }
 
-fn choose_lex() -> LexChoice {
+fn choose_lex(lexers: &[LexerBackend]) -> LexerBackend {
     println!("Selecione o Analisador Léxico:");
-    println!("1) Lexer Manual");
-    println!("2) Lexer Logos");
-    print!("Escolha: ");
-    io::stdout().flush().unwrap();
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
-    match input.trim() {
-        "1" => LexChoice::Manual,
-        "2" => LexChoice::Logos,
-        _ => {
-            println!("Opção inválida. Usando Lexer Manual como padrão.");
-            LexChoice::Manual
-        }
+    for (i, lexer) in lexers.iter().enumerate() {
+        println!("{}) Lexer {}", i + 1, lexer.name);
     }
+    let default = lexers[0];
+    read_choice(lexers).unwrap_or_else(|| {
+        println!("Opção inválida. Usando Lexer {} como padrão.", default.name);
+        default
+    })
 }
 
-fn choose_parser() -> ParseChoice {
+fn choose_parser(parsers: &[ParserBackend]) -> ParserBackend {
     println!("\nSelecione o Analisador Sintático:");
-    println!("1) Parser Manual");
-    println!("2) Parser Nom");
-    println!("3) Parser Chumsky");
+    for (i, parser) in parsers.iter().enumerate() {
+        println!("{}) Parser {}", i + 1, parser.name);
+    }
+    let default = parsers[0];
+    read_choice(parsers).unwrap_or_else(|| {
+        println!("Opção inválida. Usando Parser {} como padrão.", default.name);
+        default
+    })
+}
+
+/// Lê o número da opção escolhida (a partir de 1) na entrada padrão.
+fn read_choice<T: Copy>(options: &[T]) -> Option<T> {
     print!("Escolha: ");
     io::stdout().flush().unwrap();
 
     let mut input = String::new();
     io::stdin().read_line(&mut input).unwrap();
-    match input.trim() {
-        "1" => ParseChoice::Manual,
-        "2" => ParseChoice::Nom,
-        "3" => ParseChoice::Chumsky,
-        _ => {
-            println!("Opção inválida. Usando Parser Manual como padrão.");
-            ParseChoice::Manual
-        }
-    }
+    let index = input.trim().parse::<usize>().ok()?;
+    options.get(index.checked_sub(1)?).copied()
 }
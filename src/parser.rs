@@ -1,2 +1 @@
-pub mod ast; //ÁRVORE SINTÁTICA ABSTRATA ou ABSTRACT SYNTATIC TREE
 pub mod parser_impl;
\ No newline at end of file
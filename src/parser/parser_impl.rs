@@ -1,84 +1,191 @@
+// `Simple<Token>` é grande por natureza; é o tipo de erro padrão do Chumsky.
+#![allow(clippy::result_large_err)]
+
 use chumsky::prelude::*;
-use crate::lexer::token::Token;
-use crate::parser::ast::{Statement, Command, Expression, BinaryOp};
+use chumsky::error::SimpleReason;
+use chumsky::Stream;
+use crate::ast::{
+    AssignOp, BinaryOp, Command, Expression, ExpressionKind, Statement, StatementKind, UnaryOp,
+};
+use crate::error::{Expected, ParseError};
+use crate::span::{Position, Span, Spanned};
+use std::ops::Range;
+use crate::token::Token;
+use crate::trivia::strip_comments;
 
 pub fn parser() -> impl Parser<Token, Vec<Statement>, Error = Simple<Token>> + Clone {
-    // Parser de expressões
+    // Parser de expressões, um nível por `BinaryOp::precedence`
     let expr = recursive(|expr| {
-        let term = select! {
-            Token::Number(n) => Expression::Number(n),
-            Token::Identifier(id) => Expression::Identifier(id),
-        };
-
-        let op = just(Token::Plus).to(BinaryOp::Add)
-            .or(just(Token::Minus).to(BinaryOp::Sub));
-
-        term.clone()
-            .then(op.then(term).repeated()) // repete (op term)
-            .foldl(|lhs, (op, rhs)| Expression::Binary {
-                lhs: Box::new(lhs),
-                op,
-                rhs: Box::new(rhs),
-            })
+        let term = expecting(
+            select! {
+                Token::Number(n) => ExpressionKind::Number(n),
+                Token::Identifier(id) => ExpressionKind::Identifier(id),
+            },
+            TERMS,
+        )
+        .map_with_span(|kind, span: Range<usize>| Expression::new(kind, span.into()))
+        .or(parenthesized(expr));
+
+        let unary = choice((
+            just(Token::LogicalNot).to(UnaryOp::Not),
+            just(Token::Minus).to(UnaryOp::Negate),
+        ))
+        .map_with_span(|op, span: Range<usize>| (op, Span::from(span)))
+        .repeated()
+        .then(term)
+        .foldr(|(op, op_span), operand| Expression::unary(op, op_span, operand));
+
+        let product_op = just(Token::Asterisk).to(BinaryOp::Multiply)
+            .or(just(Token::Slash).to(BinaryOp::Divide));
+        let product = unary.clone()
+            .then(product_op.then(unary).repeated()) // repete (op termo)
+            .foldl(|left, (op, right)| Expression::binary(left, op, right));
+
+        let sum_op = just(Token::Plus).to(BinaryOp::Plus)
+            .or(just(Token::Minus).to(BinaryOp::Minus));
+        let sum = product.clone()
+            .then(sum_op.then(product).repeated())
+            .foldl(|left, (op, right)| Expression::binary(left, op, right));
+
+        let comparison_op = choice((
+            just(Token::EqualEqual).to(BinaryOp::Equal),
+            just(Token::NotEqual).to(BinaryOp::NotEqual),
+            just(Token::Less).to(BinaryOp::Less),
+            just(Token::LessEqual).to(BinaryOp::LessEqual),
+            just(Token::Greater).to(BinaryOp::Greater),
+            just(Token::GreaterEqual).to(BinaryOp::GreaterEqual),
+        ));
+        let comparison = sum.clone()
+            .then(comparison_op.then(sum).or_not())
+            .map(|(left, rest)| match rest {
+                Some((op, right)) => Expression::binary(left, op, right),
+                None => left,
+            });
+
+        let and = comparison.clone()
+            .then(just(Token::LogicalAnd).to(BinaryOp::And).then(comparison).repeated())
+            .foldl(|left, (op, right)| Expression::binary(left, op, right));
+
+        and.clone()
+            .then(just(Token::LogicalOr).to(BinaryOp::Or).then(and).repeated())
+            .foldl(|left, (op, right)| Expression::binary(left, op, right))
     });
 
+    // let nome = expr, sem o `;` (que o cabeçalho do for não tem)
+    let identifier = expecting(select! { Token::Identifier(name) => name }, NAME);
+    let let_parser = just(Token::Let)
+        .ignore_then(identifier.clone())
+        .then_ignore(just(Token::Equal))
+        .then(expr.clone())
+        .map_with_span(|(name, value), span: Range<usize>| {
+            Statement::new(StatementKind::Let { name, value }, span.into())
+        });
+
+    // nome (= | +=) expr, também sem o `;`
+    let assign_op = just(Token::Equal).to(AssignOp::Set)
+        .or(just(Token::PlusEqual).to(AssignOp::Add));
+    let assign_parser = identifier
+        .then(assign_op)
+        .then(expr.clone())
+        .map_with_span(|((name, op), value), span: Range<usize>| {
+            Statement::new(StatementKind::Assign { name, op, value }, span.into())
+        });
+
     // Parser de statements (recursivo)
     let statement = recursive(|statement| {
-        // if ( expr ) { statement } else { statement }
-        let if_parser = just(Token::If)
-            .ignore_then(just(Token::LParen))
-            .ignore_then(expr.clone())
-            .then_ignore(just(Token::RParen))
-            .then_ignore(just(Token::LBrace))
-            .then(statement.clone())
-            .then_ignore(just(Token::RBrace))
-            .then(
-                just(Token::Else)
-                    .ignore_then(just(Token::LBrace))
-                    .ignore_then(statement.clone())
-                    .then_ignore(just(Token::RBrace))
-            )
-            .map(|(cond_then, else_branch)| {
-                let (cond, then_branch) = cond_then;
-                Statement::If {
-                    condition: cond,
-                    then_branch: Box::new(then_branch),
-                    else_branch: Box::new(else_branch),
+        // { statement* }, com qualquer número de statements e blocos aninhados.
+        // Se a entrada acabar antes da `}`, o bloco fica com o que foi lido e
+        // o erro é o de delimitador não fechado.
+        let close = just(Token::RBrace).to(true).or(end().to(false).map_err(|err: Simple<Token>| {
+            Simple::expected_input_found(err.span(), [Some(Token::RBrace)], None)
+        }));
+        let block = just(Token::LBrace)
+            .map_with_span(|_, span: Range<usize>| span)
+            .then(statement.clone().repeated())
+            .then(close)
+            .validate(|((open, stmts), closed), span: Range<usize>, emit| {
+                if !closed {
+                    let end = span.end..span.end;
+                    emit(Simple::unclosed_delimiter(open, Token::LBrace, end, Token::RBrace, None));
                 }
+                Statement::new(StatementKind::Block(stmts), span.into())
+            })
+            .recover_with(nested_delimiters(
+                Token::LBrace,
+                Token::RBrace,
+                [(Token::LParen, Token::RParen)],
+                |span: Range<usize>| Statement::new(StatementKind::Error, span.into()),
+            ));
+
+        // Corpo de if/else/while/repeat/for. Se a entrada acabar ou vier uma
+        // `}` no lugar do corpo, o corpo vira um nó `Error` e o statement (com
+        // os erros já emitidos no cabeçalho) é mantido.
+        let body = statement.clone().or(missing_statement());
+
+        // if ( expr ) statement [else statement]
+        // O else, quando presente, pertence ao if mais próximo.
+        let if_parser = just(Token::If)
+            .ignore_then(parenthesized(expr.clone()))
+            .then(body.clone())
+            .then(just(Token::Else).ignore_then(body.clone()).or_not())
+            .map(|((cond, then_branch), else_branch)| StatementKind::If {
+                condition: cond,
+                then_branch: Box::new(then_branch),
+                else_branch: else_branch.map(Box::new),
             });
 
-        // while ( expr ) { statement }
+        // while ( expr ) statement
         let while_parser = just(Token::While)
-            .ignore_then(just(Token::LParen))
-            .ignore_then(expr.clone())
-            .then_ignore(just(Token::RParen))
-            .then_ignore(just(Token::LBrace))
-            .then(statement.clone())
-            .then_ignore(just(Token::RBrace))
-            .map(|(cond, body)| Statement::While {
+            .ignore_then(parenthesized(expr.clone()))
+            .then(body.clone())
+            .map(|(cond, body)| StatementKind::While {
                 condition: cond,
                 body: Box::new(body),
             });
 
-        // for ( expr ; expr ; expr ) { statement }
+        // repeat ( expr ) statement
+        let repeat_parser = just(Token::Repeat)
+            .ignore_then(parenthesized(expr.clone()))
+            .then(body.clone())
+            .map(|(count, body)| StatementKind::Repeat {
+                count,
+                body: Box::new(body),
+            });
+
+        // for ( (let | atribuição) ; expr ; atribuição ) statement
         let for_parser = just(Token::For)
-            .ignore_then(just(Token::LParen))
-            .ignore_then(expr.clone())
-            .then_ignore(just(Token::Semicolon))
-            .then(expr.clone())
-            .then_ignore(just(Token::Semicolon))
-            .then(expr.clone())
-            .then_ignore(just(Token::RParen))
-            .then_ignore(just(Token::LBrace))
-            .then(statement.clone())
-            .then_ignore(just(Token::RBrace))
-            .map(|(((init, cond), update), body)| Statement::For {
-                init,
+            .ignore_then(
+                let_parser.clone()
+                    .or(assign_parser.clone())
+                    .then_ignore(just(Token::Semicolon))
+                    .then(expr.clone())
+                    .then_ignore(just(Token::Semicolon))
+                    .then(assign_parser.clone())
+                    .delimited_by(just(Token::LParen), just(Token::RParen))
+                    .recover_with(nested_delimiters(
+                        Token::LParen,
+                        Token::RParen,
+                        [(Token::LBrace, Token::RBrace)],
+                        |span: Range<usize>| {
+                            let error = || Statement::new(StatementKind::Error, span.clone().into());
+                            ((error(), Expression::new(ExpressionKind::Error, span.clone().into())), error())
+                        },
+                    )),
+            )
+            .then(body)
+            .map(|(((init, cond), update), body)| StatementKind::For {
+                init: Box::new(init),
                 condition: cond,
-                update,
+                update: Box::new(update),
                 body: Box::new(body),
             });
 
+        // let e atribuições fora do for terminam em `;`
+        let simple_statement = let_parser.clone()
+            .or(assign_parser.clone())
+            .then_ignore(just(Token::Semicolon))
+            .map_with_span(|statement, span: Range<usize>| Statement::new(statement.kind, span.into()));
+
         // Comandos simples
         let command_parser = choice((
             just(Token::MoveUp).to(Command::MoveUp),
@@ -89,19 +196,279 @@ pub fn parser() -> impl Parser<Token, Vec<Statement>, Error = Simple<Token>> + C
             just(Token::Attack).to(Command::Attack),
             just(Token::Defend).to(Command::Defend),
         ))
-        .map(Statement::Command);
+        .map(StatementKind::Command);
 
-        choice((if_parser, while_parser, for_parser, command_parser))
+        choice((if_parser, while_parser, for_parser, repeat_parser, command_parser))
+            .map_with_span(|kind, span: Range<usize>| Statement::new(kind, span.into()))
+            .or(simple_statement)
+            .or(block)
+            .recover_with(skip_parser(statement_recovery()))
     });
 
-    // Parser final: zero ou mais statements
-    statement.repeated()
+    // Uma `}` sem par no nível mais externo vira um nó `Error` e o parse continua
+    let stray_brace = just(Token::RBrace).validate(|_, span: Range<usize>, emit| {
+        emit(Simple::expected_input_found(span.clone(), statement_starts(), Some(Token::RBrace)));
+        Statement::new(StatementKind::Error, span.into())
+    });
+
+    // Parser final: zero ou mais statements, consumindo a entrada inteira.
+    statement
+        .or(stray_brace)
+        .recover_with(skip_then_retry_until([]))
+        .repeated()
+        .then_ignore(end())
 }
 
-pub fn parse_tokens(tokens: Vec<Token>) -> Result<Vec<Statement>, Vec<String>> {
+/// Marcadores de "um número" e "um identificador" no conjunto esperado: o
+/// `select!` não diz o que aceitava. `to_parse_error` os troca por
+/// `Expected::Expression` e `Expected::Identifier`.
+const NUMBER: Token = Token::Number(0);
+const IDENTIFIER: Token = Token::Identifier(String::new());
+
+/// Tokens que iniciam um statement, além dos comandos.
+const STATEMENT_STARTS: &[Token] = &[
+    Token::If,
+    Token::While,
+    Token::For,
+    Token::Repeat,
+    Token::Let,
+    Token::LBrace,
+    IDENTIFIER,
+];
+
+/// Os sete comandos.
+const COMMANDS: &[Token] = &[
+    Token::MoveUp,
+    Token::MoveDown,
+    Token::MoveLeft,
+    Token::MoveRight,
+    Token::Jump,
+    Token::Attack,
+    Token::Defend,
+];
+
+/// Tokens que iniciam uma expressão.
+const EXPRESSION_STARTS: &[Token] = &[Token::LogicalNot, Token::LParen, Token::Minus, NUMBER, IDENTIFIER];
+const TERMS: &[Token] = &[NUMBER, IDENTIFIER];
+
+/// Operadores binários, que podem continuar qualquer expressão já completa.
+const BINARY_OPERATORS: &[Token] = &[
+    Token::Asterisk,
+    Token::Slash,
+    Token::Plus,
+    Token::Minus,
+    Token::EqualEqual,
+    Token::NotEqual,
+    Token::Less,
+    Token::LessEqual,
+    Token::Greater,
+    Token::GreaterEqual,
+    Token::LogicalAnd,
+    Token::LogicalOr,
+];
+const NAME: &[Token] = &[IDENTIFIER];
+
+/// Todos os inícios de statement, como conjunto esperado do Chumsky.
+fn statement_starts() -> impl Iterator<Item = Option<Token>> {
+    STATEMENT_STARTS.iter().chain(COMMANDS).cloned().map(Some)
+}
+
+/// Faz os erros de `parser` esperarem `expected`.
+fn expecting<O, P>(parser: P, expected: &'static [Token]) -> impl Parser<Token, O, Error = Simple<Token>> + Clone
+where
+    P: Parser<Token, O, Error = Simple<Token>> + Clone,
+{
+    parser.map_err(move |err: Simple<Token>| {
+        let found = err.found().cloned();
+        Simple::expected_input_found(err.span(), expected.iter().cloned().map(Some), found)
+    })
+}
+
+/// ( expr ). Se o conteúdo dos parênteses não for uma expressão válida, o
+/// grupo inteiro vira um `ExpressionKind::Error`.
+fn parenthesized<P>(expr: P) -> impl Parser<Token, Expression, Error = Simple<Token>> + Clone
+where
+    P: Parser<Token, Expression, Error = Simple<Token>> + Clone,
+{
+    expr.delimited_by(just(Token::LParen), just(Token::RParen))
+        .recover_with(nested_delimiters(
+            Token::LParen,
+            Token::RParen,
+            [(Token::LBrace, Token::RBrace)],
+            |span: Range<usize>| Expression::new(ExpressionKind::Error, span.into()),
+        ))
+}
+
+/// Statement que faltou no fim da entrada ou antes de uma `}`: emite o erro
+/// sem consumir nada e devolve um `StatementKind::Error` vazio. O erro na `}`
+/// aponta para ela, como o da `}` sem par que o nível externo reporta depois.
+fn missing_statement() -> impl Parser<Token, Statement, Error = Simple<Token>> + Clone {
+    let missing = |found: Option<Token>| {
+        move |span: Range<usize>, _, emit: &mut dyn FnMut(Simple<Token>)| {
+            let at = span.start..span.start;
+            emit(Simple::expected_input_found(span, statement_starts(), found.clone()));
+            Statement::new(StatementKind::Error, at.into())
+        }
+    };
+    let brace = just(Token::RBrace).map_with_span(|_, span: Range<usize>| span).rewind();
+    end()
+        .map_with_span(|_, span: Range<usize>| span)
+        .validate(missing(None))
+        .or(brace.validate(missing(Some(Token::RBrace))))
+}
+
+/// Recuperação de um statement inválido: descarta tokens até a próxima
+/// fronteira de statement e devolve um `StatementKind::Error` cobrindo o trecho.
+///
+/// A fronteira é o fim do primeiro bloco `{ ... }` (junto com os `else { ... }`
+/// que o seguirem) ou o início do próximo statement. Uma `}` sem par nunca é
+/// consumida, para que o bloco externo possa fechar normalmente. Quando nem a
+/// recuperação casa (numa `}` ou no fim da entrada), o erro é o do statement
+/// que faltou ali.
+fn statement_recovery() -> impl Parser<Token, Statement, Error = Simple<Token>> + Clone {
+    let is_brace = |t: &Token| matches!(t, Token::LBrace | Token::RBrace);
+    let starts_statement = |t: &Token| {
+        matches!(
+            t,
+            Token::If
+                | Token::While
+                | Token::For
+                | Token::Repeat
+                | Token::Let
+                | Token::MoveUp
+                | Token::MoveDown
+                | Token::MoveLeft
+                | Token::MoveRight
+                | Token::Jump
+                | Token::Attack
+                | Token::Defend
+        )
+    };
+
+    // { ... } balanceado, consumido por inteiro
+    let group = recursive(|group| {
+        group
+            .or(filter(move |t: &Token| !is_brace(t)).ignored())
+            .repeated()
+            .delimited_by(just(Token::LBrace), just(Token::RBrace))
+            .ignored()
+    });
+    let header = filter(move |t: &Token| !is_brace(t) && !starts_statement(t)).repeated();
+    let else_header = filter(move |t: &Token| !is_brace(t)).repeated();
+
+    filter(move |t: &Token| !is_brace(t))
+        .ignore_then(header)
+        .ignore_then(
+            group
+                .clone()
+                .then(just(Token::Else).ignore_then(else_header).ignore_then(group).repeated())
+                .or_not(),
+        )
+        .map_with_span(|_, span: Range<usize>| Statement::new(StatementKind::Error, span.into()))
+        .map_err(|err: Simple<Token>| {
+            let found = err.found().cloned();
+            Simple::expected_input_found(err.span(), statement_starts(), found)
+        })
+}
+
+/// Parseia os tokens exigindo um programa sem erros.
+pub fn parse_tokens(tokens: Vec<Spanned<Token>>) -> Result<Vec<Statement>, Vec<ParseError>> {
+    match parse_tokens_recovery(tokens) {
+        (Some(ast), errors) if errors.is_empty() => Ok(ast),
+        (_, errors) => Err(errors),
+    }
+}
+
+/// Parseia os tokens recuperando-se dos erros: reporta todos os erros do
+/// programa e, quando possível, devolve uma AST parcial com nós `Error` no
+/// lugar dos trechos inválidos.
+pub fn parse_tokens_recovery(tokens: Vec<Spanned<Token>>) -> (Option<Vec<Statement>>, Vec<ParseError>) {
     let p = parser();
-    match p.parse(tokens) {
-        Ok(ast) => Ok(ast),
-        Err(errors) => Err(errors.into_iter().map(|err| format!("{:#?}", err)).collect()),
+    // Comentários são trivia: não chegam à gramática
+    let tokens = strip_comments(tokens);
+    // O fim da entrada é marcado logo após o último token.
+    let end = tokens.last().map_or(0, |t| t.span.end);
+    let stream = Stream::from_iter(
+        end..end,
+        tokens.iter().map(|t| (t.value.clone(), t.span.into())),
+    );
+    let (ast, errors) = p.parse_recovery(stream);
+    // As recuperações aninhadas podem reportar o mesmo erro mais de uma vez,
+    // e não necessariamente na ordem do código
+    let mut errors: Vec<ParseError> = errors.iter().map(|err| to_parse_error(err, &tokens)).collect();
+    errors.sort_by_key(|err| err.span().start);
+    errors.dedup();
+    (ast, errors)
+}
+
+/// Converte o erro do Chumsky no erro comum aos parsers. `tokens` dão as
+/// posições (linha:coluna) dos trechos, que o Chumsky não conhece.
+fn to_parse_error(err: &Simple<Token>, tokens: &[Spanned<Token>]) -> ParseError {
+    let span: Span = err.span().into();
+    let position = position_at(tokens, span.start);
+    let accepts = |starts: &[Token]| starts.iter().all(|t| err.expected().any(|e| e.as_ref() == Some(t)));
+    // Resume os conjuntos completos: os inícios de statement viram "a
+    // statement"; os de expressão, "an expression"; os comandos, "a command"
+    let mut expected = Vec::new();
+    let mut covered: Vec<&Token> = Vec::new();
+    if accepts(STATEMENT_STARTS) && accepts(COMMANDS) {
+        covered.extend(STATEMENT_STARTS.iter().chain(COMMANDS));
+        expected.push(Expected::Statement);
+    } else if accepts(COMMANDS) {
+        covered.extend(COMMANDS);
+        expected.push(Expected::Command);
+    }
+    if accepts(EXPRESSION_STARTS) {
+        covered.extend(EXPRESSION_STARTS);
+        expected.push(Expected::Expression);
     }
-}
\ No newline at end of file
+    // Depois de uma expressão completa, o Chumsky lista cada operador que
+    // poderia continuá-la; como os outros parsers, fica só o que a fecha
+    let continues = |t: &Option<Token>| t.as_ref().is_some_and(|t| BINARY_OPERATORS.contains(t));
+    if err.expected().any(|t| !continues(t)) {
+        covered.extend(BINARY_OPERATORS);
+    }
+    for token in err.expected() {
+        match token {
+            Some(token) if covered.contains(&token) => {}
+            Some(Token::Identifier(_)) => expected.push(Expected::Identifier),
+            Some(Token::Number(_)) => expected.push(Expected::Expression),
+            Some(token) => expected.push(Expected::Token(token.clone())),
+            None => expected.push(Expected::EndOfInput),
+        }
+    }
+    // O conjunto do Chumsky não tem ordem; ordena pelo texto para estabilidade
+    expected.sort_by_cached_key(|e| e.to_string());
+    expected.dedup();
+    match (err.reason(), err.found()) {
+        (SimpleReason::Unclosed { span: open, delimiter }, _) => ParseError::UnclosedDelimiter {
+            delimiter: delimiter.clone(),
+            open: open.clone().into(),
+            open_position: position_at(tokens, open.start),
+            expected,
+            span,
+            position,
+        },
+        // A gramática não emite erros customizados; sobram os inesperados
+        (_, Some(found)) => ParseError::UnexpectedToken {
+            expected,
+            found: found.clone(),
+            span,
+            position,
+        },
+        (_, None) => ParseError::UnexpectedEof {
+            expected,
+            span,
+            position,
+        },
+    }
+}
+
+/// Posição do token que começa em `offset`; depois do último token, a
+/// posição logo após ele.
+fn position_at(tokens: &[Spanned<Token>], offset: usize) -> Position {
+    match tokens.iter().find(|t| t.span.start >= offset) {
+        Some(token) => token.position,
+        None => tokens.last().map(Spanned::end_position).unwrap_or_default(),
+    }
+}
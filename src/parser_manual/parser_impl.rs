@@ -1,62 +1,81 @@
-use crate::lexer_manual::token::Token;
-use crate::parser_manual::ast::*;
+use crate::ast::*;
+use crate::error::{Expected, ParseError};
+use crate::span::{Position, Span, Spanned};
+use crate::token::Token;
+use crate::trivia::strip_comments;
 use std::iter::Peekable;
 use std::vec::IntoIter;
 
 pub struct Parser {
-    tokens: Peekable<IntoIter<Token>>,
+    tokens: Peekable<IntoIter<Spanned<Token>>>,
+    /// Trecho do último token consumido, usado para fechar o span dos nós.
+    last_span: Span,
+    /// Posição logo após o último token consumido, onde fica o fim da entrada.
+    end_position: Position,
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
+    /// Cria o parser; os comentários são trivia e ficam de fora.
+    pub fn new(tokens: Vec<Spanned<Token>>) -> Self {
         Parser {
-            tokens: tokens.into_iter().peekable(),
+            tokens: strip_comments(tokens).into_iter().peekable(),
+            last_span: Span::default(),
+            end_position: Position::default(),
         }
     }
 
     /// Parseia o programa inteiro, retornando uma lista de statements
-    pub fn parse_program(&mut self) -> Result<Vec<Statement>, String> {
+    pub fn parse_program(&mut self) -> Result<Vec<Statement>, ParseError> {
         let mut statements = Vec::new();
-        while self.tokens.peek().is_some() {
+        while self.peek().is_some() {
             statements.push(self.parse_statement()?);
         }
         Ok(statements)
     }
 
     /// Parseia um statement, que pode ser um comando ou uma estrutura de controle
-    fn parse_statement(&mut self) -> Result<Statement, String> {
-        if let Some(token) = self.tokens.peek() {
+    fn parse_statement(&mut self) -> Result<Statement, ParseError> {
+        if let Some(token) = self.peek() {
             match token {
                 Token::If => self.parse_if_stmt(),
                 Token::While => self.parse_while_stmt(),
                 Token::For => self.parse_for_stmt(),
+                Token::Repeat => self.parse_repeat_stmt(),
                 Token::LBrace => self.parse_block(),
+                Token::Let => {
+                    let statement = self.parse_let()?;
+                    self.finish_simple_statement(statement)
+                }
+                Token::Identifier(_) => {
+                    let statement = self.parse_assignment()?;
+                    self.finish_simple_statement(statement)
+                }
                 // Caso não seja nenhum dos casos acima, assume comando
                 _ => self.parse_command(),
             }
         } else {
-            Err("Fim inesperado da entrada ao parsear statement".to_string())
+            Err(self.unexpected_eof(Expected::Statement))
         }
     }
 
     /// Parseia um bloco: { statement* }
-    fn parse_block(&mut self) -> Result<Statement, String> {
-        self.consume(Token::LBrace)?;
+    fn parse_block(&mut self) -> Result<Statement, ParseError> {
+        let start = self.consume(Token::LBrace)?;
         let mut stmts = Vec::new();
-        while let Some(token) = self.tokens.peek() {
+        while let Some(token) = self.peek() {
             if *token == Token::RBrace {
                 break;
             }
             stmts.push(self.parse_statement()?);
         }
-        self.consume(Token::RBrace)?;
-        Ok(Statement::Block(stmts))
+        let end = self.consume(Token::RBrace)?;
+        Ok(Statement::new(StatementKind::Block(stmts), start.merge(end)))
     }
 
     /// Parseia um comando simples (movimento ou ação)
-    fn parse_command(&mut self) -> Result<Statement, String> {
-        if let Some(token) = self.tokens.next() {
-            let cmd = match token {
+    fn parse_command(&mut self) -> Result<Statement, ParseError> {
+        if let Some(token) = self.next() {
+            let cmd = match token.value {
                 Token::MoveUp => Command::MoveUp,
                 Token::MoveDown => Command::MoveDown,
                 Token::MoveLeft => Command::MoveLeft,
@@ -64,124 +83,311 @@ impl Parser {
                 Token::Jump => Command::Jump,
                 Token::Attack => Command::Attack,
                 Token::Defend => Command::Defend,
-                _ => return Err(format!("Token inesperado em comando: {:?}", token)),
+                other => return Err(ParseError::UnexpectedToken {
+                        expected: vec![Expected::Statement],
+                        found: other,
+                        span: token.span,
+                        position: token.position,
+                    }),
             };
-            Ok(Statement::Command(cmd))
+            Ok(Statement::new(StatementKind::Command(cmd), token.span))
         } else {
-            Err("Fim inesperado da entrada ao parsear comando".to_string())
+            Err(self.unexpected_eof(Expected::Statement))
         }
     }
 
-    /// Parseia um if: if ( expr ) { statement } else { statement }
-    fn parse_if_stmt(&mut self) -> Result<Statement, String> {
-        self.consume(Token::If)?;
+    /// Parseia um if: if ( expr ) statement [else statement]
+    /// O else, quando presente, pertence a este if, o mais próximo.
+    fn parse_if_stmt(&mut self) -> Result<Statement, ParseError> {
+        let start = self.consume(Token::If)?;
         self.consume(Token::LParen)?;
         let condition = self.parse_expression()?;
         self.consume(Token::RParen)?;
         let then_branch = Box::new(self.parse_statement()?);
-        self.consume(Token::Else)?;
-        let else_branch = Box::new(self.parse_statement()?);
-        Ok(Statement::If {
-            condition,
-            then_branch,
-            else_branch,
-        })
+        let else_branch = if self.peek() == Some(&Token::Else) {
+            self.next();
+            Some(Box::new(self.parse_statement()?))
+        } else {
+            None
+        };
+        Ok(Statement::new(
+            StatementKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            },
+            start.merge(self.last_span),
+        ))
     }
 
-    /// Parseia um while: while ( expr ) { statement }
-    fn parse_while_stmt(&mut self) -> Result<Statement, String> {
-        self.consume(Token::While)?;
+    /// Parseia um while: while ( expr ) statement
+    fn parse_while_stmt(&mut self) -> Result<Statement, ParseError> {
+        let start = self.consume(Token::While)?;
         self.consume(Token::LParen)?;
         let condition = self.parse_expression()?;
         self.consume(Token::RParen)?;
         let body = Box::new(self.parse_statement()?);
-        Ok(Statement::While { condition, body })
+        Ok(Statement::new(
+            StatementKind::While { condition, body },
+            start.merge(self.last_span),
+        ))
     }
 
-    /// Parseia um for: for ( expr ; expr ; expr ) { statement }
-    fn parse_for_stmt(&mut self) -> Result<Statement, String> {
-        self.consume(Token::For)?;
+    /// Parseia um repeat: repeat ( expr ) statement
+    fn parse_repeat_stmt(&mut self) -> Result<Statement, ParseError> {
+        let start = self.consume(Token::Repeat)?;
         self.consume(Token::LParen)?;
-        let init = self.parse_expression()?;
+        let count = self.parse_expression()?;
+        self.consume(Token::RParen)?;
+        let body = Box::new(self.parse_statement()?);
+        Ok(Statement::new(
+            StatementKind::Repeat { count, body },
+            start.merge(self.last_span),
+        ))
+    }
+
+    /// Parseia um for: for ( (let | atribuição) ; expr ; atribuição ) statement
+    fn parse_for_stmt(&mut self) -> Result<Statement, ParseError> {
+        let start = self.consume(Token::For)?;
+        self.consume(Token::LParen)?;
+        let init = match self.peek() {
+            Some(Token::Let) => self.parse_let()?,
+            Some(Token::Identifier(_)) => self.parse_assignment()?,
+            _ => return Err(self.unexpected(vec![Expected::Token(Token::Let), Expected::Identifier])),
+        };
         self.consume(Token::Semicolon)?;
         let condition = self.parse_expression()?;
         self.consume(Token::Semicolon)?;
-        let update = self.parse_expression()?;
+        let update = self.parse_assignment()?;
         self.consume(Token::RParen)?;
         let body = Box::new(self.parse_statement()?);
-        Ok(Statement::For {
-            init,
-            condition,
-            update,
-            body,
-        })
+        Ok(Statement::new(
+            StatementKind::For {
+                init: Box::new(init),
+                condition,
+                update: Box::new(update),
+                body,
+            },
+            start.merge(self.last_span),
+        ))
     }
 
-    /// Parseia uma expressão aritmética simples (identificador, número ou parênteses).
-    /// Suporta a operação binária de adição e subtração.
-    fn parse_expression(&mut self) -> Result<Expression, String> {
-        let mut expr = self.parse_term()?;
-        while let Some(token) = self.tokens.peek() {
-            match token {
-                Token::Plus | Token::Minus => {
-                    let op = match self.tokens.next().unwrap() {
-                        Token::Plus => BinaryOp::Plus,
-                        Token::Minus => BinaryOp::Minus,
-                        _ => unreachable!(),
-                    };
-                    let right = self.parse_term()?;
-                    expr = Expression::Binary {
-                        left: Box::new(expr),
-                        op,
-                        right: Box::new(right),
-                    };
-                }
-                _ => break,
+    /// Parseia uma declaração sem o `;`: let nome = expr
+    fn parse_let(&mut self) -> Result<Statement, ParseError> {
+        let start = self.consume(Token::Let)?;
+        let name = self.consume_identifier()?;
+        self.consume(Token::Equal)?;
+        let value = self.parse_expression()?;
+        let span = start.merge(value.span);
+        Ok(Statement::new(StatementKind::Let { name, value }, span))
+    }
+
+    /// Parseia uma atribuição sem o `;`: nome (= | +=) expr
+    fn parse_assignment(&mut self) -> Result<Statement, ParseError> {
+        let start = self.peek_span();
+        let name = self.consume_identifier()?;
+        let op = match self.peek() {
+            Some(Token::Equal) => AssignOp::Set,
+            Some(Token::PlusEqual) => AssignOp::Add,
+            _ => {
+                return Err(self.unexpected(vec![
+                    Expected::Token(Token::Equal),
+                    Expected::Token(Token::PlusEqual),
+                ]))
             }
+        };
+        self.next();
+        let value = self.parse_expression()?;
+        let span = start.merge(value.span);
+        Ok(Statement::new(StatementKind::Assign { name, op, value }, span))
+    }
+
+    /// Consome o `;` que encerra um let ou uma atribuição fora do for.
+    fn finish_simple_statement(&mut self, mut statement: Statement) -> Result<Statement, ParseError> {
+        let end = self.consume(Token::Semicolon)?;
+        statement.span = statement.span.merge(end);
+        Ok(statement)
+    }
+
+    /// Parseia uma expressão: um método por nível de [`BinaryOp::precedence`],
+    /// do mais fraco ao mais forte.
+    fn parse_expression(&mut self) -> Result<Expression, ParseError> {
+        self.parse_binary_level(Self::parse_and, |token| match token {
+            Token::LogicalOr => Some(BinaryOp::Or),
+            _ => None,
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, ParseError> {
+        self.parse_binary_level(Self::parse_comparison, |token| match token {
+            Token::LogicalAnd => Some(BinaryOp::And),
+            _ => None,
+        })
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expression, ParseError> {
+        let left = self.parse_sum()?;
+        let op = match self.peek() {
+            Some(Token::EqualEqual) => BinaryOp::Equal,
+            Some(Token::NotEqual) => BinaryOp::NotEqual,
+            Some(Token::Less) => BinaryOp::Less,
+            Some(Token::LessEqual) => BinaryOp::LessEqual,
+            Some(Token::Greater) => BinaryOp::Greater,
+            Some(Token::GreaterEqual) => BinaryOp::GreaterEqual,
+            _ => return Ok(left),
+        };
+        self.next();
+        let right = self.parse_sum()?;
+        Ok(Expression::binary(left, op, right))
+    }
+
+    fn parse_sum(&mut self) -> Result<Expression, ParseError> {
+        self.parse_binary_level(Self::parse_product, |token| match token {
+            Token::Plus => Some(BinaryOp::Plus),
+            Token::Minus => Some(BinaryOp::Minus),
+            _ => None,
+        })
+    }
+
+    fn parse_product(&mut self) -> Result<Expression, ParseError> {
+        self.parse_binary_level(Self::parse_unary, |token| match token {
+            Token::Asterisk => Some(BinaryOp::Multiply),
+            Token::Slash => Some(BinaryOp::Divide),
+            _ => None,
+        })
+    }
+
+    /// Parseia um nível de operadores binários associativos à esquerda:
+    /// operando (op operando)*
+    fn parse_binary_level(
+        &mut self,
+        operand: fn(&mut Self) -> Result<Expression, ParseError>,
+        operator: fn(&Token) -> Option<BinaryOp>,
+    ) -> Result<Expression, ParseError> {
+        let mut expr = operand(self)?;
+        while let Some(op) = self.peek().and_then(operator) {
+            self.next();
+            let right = operand(self)?;
+            expr = Expression::binary(expr, op, right);
         }
         Ok(expr)
     }
 
+    fn parse_unary(&mut self) -> Result<Expression, ParseError> {
+        let op = match self.peek() {
+            Some(Token::LogicalNot) => UnaryOp::Not,
+            Some(Token::Minus) => UnaryOp::Negate,
+            _ => return self.parse_term(),
+        };
+        let op_span = self.next().map(|t| t.span).unwrap_or_default();
+        let operand = self.parse_unary()?;
+        Ok(Expression::unary(op, op_span, operand))
+    }
+
     /// Parseia um termo: número, identificador ou expressão entre parênteses.
-    fn parse_term(&mut self) -> Result<Expression, String> {
-        if let Some(token) = self.tokens.next() {
-            match token {
-                Token::Number(n) => Ok(Expression::Number(n)),
-                Token::Identifier(id) => Ok(Expression::Identifier(id)),
+    fn parse_term(&mut self) -> Result<Expression, ParseError> {
+        if let Some(token) = self.next() {
+            match token.value {
+                Token::Number(n) => Ok(Expression::new(ExpressionKind::Number(n), token.span)),
+                Token::Identifier(id) => {
+                    Ok(Expression::new(ExpressionKind::Identifier(id), token.span))
+                }
                 Token::LParen => {
                     let expr = self.parse_expression()?;
                     self.consume(Token::RParen)?;
                     Ok(expr)
                 }
-                _ => Err(format!("Token inesperado em expressão: {:?}", token)),
+                other => Err(ParseError::UnexpectedToken {
+                    expected: vec![Expected::Expression],
+                    found: other,
+                    span: token.span,
+                    position: token.position,
+                }),
             }
         } else {
-            Err("Fim inesperado da entrada ao parsear expressão".to_string())
+            Err(self.unexpected_eof(Expected::Expression))
+        }
+    }
+
+    /// Olha o próximo token sem consumi-lo.
+    fn peek(&mut self) -> Option<&Token> {
+        self.tokens.peek().map(|t| &t.value)
+    }
+
+    /// Consome o próximo token, lembrando seu trecho.
+    fn next(&mut self) -> Option<Spanned<Token>> {
+        let token = self.tokens.next()?;
+        self.last_span = token.span;
+        self.end_position = token.end_position();
+        Some(token)
+    }
+
+    /// Trecho do próximo token, ou o fim do último consumido.
+    fn peek_span(&mut self) -> Span {
+        match self.tokens.peek() {
+            Some(token) => token.span,
+            None => Span::new(self.last_span.end, self.last_span.end),
+        }
+    }
+
+    /// Consome um identificador, retornando o nome.
+    fn consume_identifier(&mut self) -> Result<String, ParseError> {
+        if let Some(Token::Identifier(name)) = self.peek() {
+            let name = name.clone();
+            self.next();
+            Ok(name)
+        } else {
+            Err(self.unexpected(vec![Expected::Identifier]))
+        }
+    }
+
+    /// Erro no próximo token, sem consumi-lo, ou no fim da entrada.
+    fn unexpected(&mut self, expected: Vec<Expected>) -> ParseError {
+        match self.tokens.peek() {
+            Some(token) => ParseError::UnexpectedToken {
+                expected,
+                found: token.value.clone(),
+                span: token.span,
+                position: token.position,
+            },
+            None => self.end_of_input(expected),
         }
     }
 
-    /// Função auxiliar para consumir um token esperado.
-    fn consume(&mut self, expected: Token) -> Result<(), String>
-    where
-        Token: PartialEq,
-    {
-        if let Some(token) = self.tokens.next() {
-            if token == expected {
-                Ok(())
+    /// Função auxiliar para consumir um token esperado, retornando seu trecho.
+    fn consume(&mut self, expected: Token) -> Result<Span, ParseError> {
+        if let Some(token) = self.next() {
+            if token.value == expected {
+                Ok(token.span)
             } else {
-                Err(format!("Esperado token {:?}, mas encontrado {:?}", expected, token))
+                Err(ParseError::UnexpectedToken {
+                    expected: vec![Expected::Token(expected)],
+                    found: token.value,
+                    span: token.span,
+                    position: token.position,
+                })
             }
         } else {
-            Err(format!("Esperado token {:?}, mas chegou ao fim da entrada", expected))
+            Err(self.unexpected_eof(Expected::Token(expected)))
         }
     }
-}
 
-pub fn parse(tokens: Vec<String>) -> Result<String, String> {
-    if tokens.is_empty() {
-        Err("No tokens to parse".to_string())
-    } else {
-        // Para demonstração, simplesmente junta os tokens em uma String representando a AST
-        Ok(format!("AST (Manual): {}", tokens.join(" ")))
+    /// Erro de fim de entrada, apontando logo após o último token consumido.
+    fn unexpected_eof(&self, expected: Expected) -> ParseError {
+        self.end_of_input(vec![expected])
+    }
+
+    fn end_of_input(&self, expected: Vec<Expected>) -> ParseError {
+        let end = self.last_span.end;
+        ParseError::UnexpectedEof {
+            expected,
+            span: Span::new(end, end),
+            position: self.end_position,
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Parseia os tokens com o parser descendente recursivo.
+pub fn parse(tokens: Vec<Spanned<Token>>) -> Result<Vec<Statement>, Vec<ParseError>> {
+    Parser::new(tokens).parse_program().map_err(|e| vec![e])
+}
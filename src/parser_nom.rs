@@ -1,2 +1,3 @@
-pub mod ast; //ÁRVORE SINTÁTICA ABSTRATA ou ABSTRACT SYNTATIC TREE
-pub mod parser_impl;
\ No newline at end of file
+pub mod error;
+pub mod parser_impl;
+pub mod tokens;
\ No newline at end of file
@@ -0,0 +1,50 @@
+use crate::error::Expected;
+use crate::parser_nom::tokens::Tokens;
+use nom::error::{ErrorKind, ParseError};
+use nom::Input;
+use std::cmp::Ordering;
+
+/// Erro dos combinadores: guarda onde parou e o que era esperado ali.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NomError<'a> {
+    pub input: Tokens<'a>,
+    pub expected: Vec<Expected>,
+}
+
+impl<'a> NomError<'a> {
+    pub fn expected(input: Tokens<'a>, expected: Expected) -> Self {
+        NomError {
+            input,
+            expected: vec![expected],
+        }
+    }
+}
+
+impl<'a> ParseError<Tokens<'a>> for NomError<'a> {
+    fn from_error_kind(input: Tokens<'a>, _kind: ErrorKind) -> Self {
+        NomError {
+            input,
+            expected: Vec::new(),
+        }
+    }
+
+    fn append(_input: Tokens<'a>, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    /// Entre alternativas, fica o erro que avançou mais; empatadas, junta os esperados.
+    fn or(mut self, other: Self) -> Self {
+        match self.input.input_len().cmp(&other.input.input_len()) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal => {
+                for expected in other.expected {
+                    if !self.expected.contains(&expected) {
+                        self.expected.push(expected);
+                    }
+                }
+                self
+            }
+        }
+    }
+}
@@ -1,159 +1,366 @@
-/* 
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_while1},
-    character::complete::{char, digit1, multispace0},
-    combinator::{map, map_res},
+    combinator::{cut, map_opt, opt},
+    error::ParseError as _,
     multi::many0,
     sequence::{delimited, preceded},
-    IResult,
+    IResult, Input, Parser,
 };
-use crate::parser::ast::*;
+use crate::ast::*;
+use crate::error::{Expected, ParseError};
+use crate::parser_nom::error::NomError;
+use crate::parser_nom::tokens::Tokens;
+use crate::span::{Span, Spanned};
+use crate::token::Token;
+use crate::trivia::strip_comments;
 
-pub fn parse_program(input: &str) -> IResult<&str, Vec<Statement>> {
-    // many0 retorna um parser. Para usá-lo diretamente,
-    // chamamos (input) no final, assim:
-    many0(delimited(multispace0, parse_statement, multispace0))(input)
+type NomResult<'a, O> = IResult<Tokens<'a>, O, NomError<'a>>;
+
+pub fn parse_program(input: Tokens) -> NomResult<Vec<Statement>> {
+    let (input, statements) = many0(parse_statement).parse(input)?;
+    if input.input_len() > 0 {
+        // many0 parou antes do fim: tenta de novo só para obter o erro
+        parse_statement(input)?;
+    }
+    Ok((input, statements))
+}
+
+// ---------------------------------------------
+// Funções auxiliares sobre tokens
+// ---------------------------------------------
+
+/// Consome qualquer token.
+fn any_token<'a>(input: Tokens<'a>) -> NomResult<'a, &'a Spanned<Token>> {
+    match input.first() {
+        Some(token) => Ok((Tokens::new(&input.tokens[1..]), token)),
+        None => Err(nom::Err::Error(NomError::from_error_kind(
+            input,
+            nom::error::ErrorKind::Eof,
+        ))),
+    }
+}
+
+/// Consome exatamente o token esperado, retornando seu trecho.
+fn token<'a>(expected: Token) -> impl Parser<Tokens<'a>, Output = Span, Error = NomError<'a>> {
+    move |input: Tokens<'a>| match input.first() {
+        Some(t) if t.value == expected => Ok((input.take_from(1), t.span)),
+        _ => Err(nom::Err::Error(NomError::expected(input, Expected::Token(expected.clone())))),
+    }
+}
+
+/// Consome um identificador, retornando o nome e o trecho.
+fn identifier(input: Tokens) -> NomResult<(String, Span)> {
+    match input.first() {
+        Some(Spanned {
+            value: Token::Identifier(name),
+            span,
+            ..
+        }) => Ok((input.take_from(1), (name.clone(), *span))),
+        _ => Err(nom::Err::Error(NomError::expected(input, Expected::Identifier))),
+    }
+}
+
+/// Troca o conjunto esperado de um erro recuperável por uma descrição só,
+/// como "a statement", no lugar da lista de cada alternativa.
+fn expecting<'a, O>(result: NomResult<'a, O>, what: Expected) -> NomResult<'a, O> {
+    result.map_err(|err| match err {
+        nom::Err::Error(err) => nom::Err::Error(NomError::expected(err.input, what)),
+        other => other,
+    })
 }
 
 // ---------------------------------------------
 // Funções de Statement
 // ---------------------------------------------
-fn parse_statement(input: &str) -> IResult<&str, Statement> {
-    alt((
+fn parse_statement(input: Tokens) -> NomResult<Statement> {
+    let result = alt((
         parse_if,
         parse_while,
         parse_for,
+        parse_repeat,
         parse_block,
-        parse_command
-    ))(input)
+        parse_simple_statement,
+        parse_command,
+    ))
+    .parse(input);
+    expecting(result, Expected::Statement)
 }
 
 /// Bloco: { statement* }
-fn parse_block(input: &str) -> IResult<&str, Statement> {
-    let (input, stmts) = delimited(
-        char('{'),
-        many0(delimited(multispace0, parse_statement, multispace0)),
-        char('}')
-    )(input)?;
-    Ok((input, Statement::Block(stmts)))
+fn parse_block(input: Tokens) -> NomResult<Statement> {
+    let (input, start) = token(Token::LBrace).parse(input)?;
+    let (input, stmts) = cut(many0(parse_statement)).parse(input)?;
+    if input.input_len() > 0 && token(Token::RBrace).parse(input).is_err() {
+        // many0 parou num token que não fecha o bloco: o erro é o do statement
+        cut(parse_statement).parse(input)?;
+    }
+    let (input, end) = cut(token(Token::RBrace)).parse(input)?;
+    Ok((input, Statement::new(StatementKind::Block(stmts), start.merge(end))))
 }
 
 /// Comando simples: move_up, move_down, ...
-fn parse_command(input: &str) -> IResult<&str, Statement> {
-    let (input, cmd) = alt((
-        map(tag("move_up"), |_| Command::MoveUp),
-        map(tag("move_down"), |_| Command::MoveDown),
-        map(tag("move_left"), |_| Command::MoveLeft),
-        map(tag("move_right"), |_| Command::MoveRight),
-        map(tag("jump"), |_| Command::Jump),
-        map(tag("attack"), |_| Command::Attack),
-        map(tag("defend"), |_| Command::Defend),
-    ))(input)?;
-    Ok((input, Statement::Command(cmd)))
-}
-
-/// if ( expr ) { statement } else { statement }
-fn parse_if(input: &str) -> IResult<&str, Statement> {
-    let (input, _) = tag("if")(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, condition) = delimited(
-        char('('),
-        preceded(multispace0, parse_expression),
-        preceded(multispace0, char(')'))
-    )(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, then_branch) = parse_block(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, _) = tag("else")(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, else_branch) = parse_block(input)?;
-    Ok((input, Statement::If {
-        condition,
-        then_branch: Box::new(then_branch),
-        else_branch: Box::new(else_branch),
-    }))
-}
-
-/// while ( expr ) { statement }
-fn parse_while(input: &str) -> IResult<&str, Statement> {
-    let (input, _) = tag("while")(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, condition) = delimited(
-        char('('),
-        preceded(multispace0, parse_expression),
-        preceded(multispace0, char(')'))
-    )(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, body) = parse_block(input)?;
-    Ok((input, Statement::While {
-        condition,
-        body: Box::new(body),
-    }))
-}
-
-/// for ( expr ; expr ; expr ) { statement }
-fn parse_for(input: &str) -> IResult<&str, Statement> {
-    let (input, _) = tag("for")(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, _) = char('(')(input)?;
-    let (input, init) = delimited(multispace0, parse_expression, multispace0)(input)?;
-    let (input, _) = char(';')(input)?;
-    let (input, condition) = delimited(multispace0, parse_expression, multispace0)(input)?;
-    let (input, _) = char(';')(input)?;
-    let (input, update) = delimited(multispace0, parse_expression, multispace0)(input)?;
-    let (input, _) = char(')')(input)?;
-    let (input, _) = multispace0(input)?;
-    let (input, body) = parse_block(input)?;
-    Ok((input, Statement::For {
-        init,
-        condition,
-        update,
-        body: Box::new(body),
-    }))
+fn parse_command(input: Tokens) -> NomResult<Statement> {
+    map_opt(any_token, |t| {
+        let cmd = match t.value {
+            Token::MoveUp => Command::MoveUp,
+            Token::MoveDown => Command::MoveDown,
+            Token::MoveLeft => Command::MoveLeft,
+            Token::MoveRight => Command::MoveRight,
+            Token::Jump => Command::Jump,
+            Token::Attack => Command::Attack,
+            Token::Defend => Command::Defend,
+            _ => return None,
+        };
+        Some(Statement::new(StatementKind::Command(cmd), t.span))
+    })
+    .parse(input)
+}
+
+/// ( expr ), usado pelas condições de if e while e pela contagem do repeat
+fn parse_condition(input: Tokens) -> NomResult<Expression> {
+    delimited(token(Token::LParen), parse_expression, token(Token::RParen)).parse(input)
+}
+
+/// if ( expr ) statement [else statement]
+/// O else, quando presente, pertence a este if, o mais próximo.
+fn parse_if(input: Tokens) -> NomResult<Statement> {
+    let (input, start) = token(Token::If).parse(input)?;
+    let (input, condition) = cut(parse_condition).parse(input)?;
+    let (input, then_branch) = cut(parse_statement).parse(input)?;
+    let (input, else_branch) =
+        opt(preceded(token(Token::Else), cut(parse_statement))).parse(input)?;
+    let end = else_branch.as_ref().unwrap_or(&then_branch).span;
+    Ok((input, Statement::new(
+        StatementKind::If {
+            condition,
+            then_branch: Box::new(then_branch),
+            else_branch: else_branch.map(Box::new),
+        },
+        start.merge(end),
+    )))
+}
+
+/// while ( expr ) statement
+fn parse_while(input: Tokens) -> NomResult<Statement> {
+    let (input, start) = token(Token::While).parse(input)?;
+    let (input, condition) = cut(parse_condition).parse(input)?;
+    let (input, body) = cut(parse_statement).parse(input)?;
+    let span = start.merge(body.span);
+    Ok((input, Statement::new(
+        StatementKind::While {
+            condition,
+            body: Box::new(body),
+        },
+        span,
+    )))
+}
+
+/// repeat ( expr ) statement
+fn parse_repeat(input: Tokens) -> NomResult<Statement> {
+    let (input, start) = token(Token::Repeat).parse(input)?;
+    let (input, count) = cut(parse_condition).parse(input)?;
+    let (input, body) = cut(parse_statement).parse(input)?;
+    let span = start.merge(body.span);
+    Ok((input, Statement::new(
+        StatementKind::Repeat {
+            count,
+            body: Box::new(body),
+        },
+        span,
+    )))
+}
+
+/// for ( (let | atribuição) ; expr ; atribuição ) statement
+fn parse_for(input: Tokens) -> NomResult<Statement> {
+    let (input, start) = token(Token::For).parse(input)?;
+    let (input, _) = cut(token(Token::LParen)).parse(input)?;
+    let (input, init) = cut(alt((parse_let, parse_assignment))).parse(input)?;
+    let (input, _) = cut(token(Token::Semicolon)).parse(input)?;
+    let (input, condition) = cut(parse_expression).parse(input)?;
+    let (input, _) = cut(token(Token::Semicolon)).parse(input)?;
+    let (input, update) = cut(parse_assignment).parse(input)?;
+    let (input, _) = cut(token(Token::RParen)).parse(input)?;
+    let (input, body) = cut(parse_statement).parse(input)?;
+    let span = start.merge(body.span);
+    Ok((input, Statement::new(
+        StatementKind::For {
+            init: Box::new(init),
+            condition,
+            update: Box::new(update),
+            body: Box::new(body),
+        },
+        span,
+    )))
+}
+
+/// let ou atribuição fora do for, terminados em `;`
+fn parse_simple_statement(input: Tokens) -> NomResult<Statement> {
+    let (input, mut statement) = alt((parse_let, parse_assignment)).parse(input)?;
+    let (input, end) = cut(token(Token::Semicolon)).parse(input)?;
+    statement.span = statement.span.merge(end);
+    Ok((input, statement))
+}
+
+/// let nome = expr, sem o `;`
+fn parse_let(input: Tokens) -> NomResult<Statement> {
+    let (input, start) = token(Token::Let).parse(input)?;
+    let (input, (name, _)) = cut(identifier).parse(input)?;
+    let (input, _) = cut(token(Token::Equal)).parse(input)?;
+    let (input, value) = cut(parse_expression).parse(input)?;
+    let span = start.merge(value.span);
+    Ok((input, Statement::new(StatementKind::Let { name, value }, span)))
+}
+
+/// nome (= | +=) expr, sem o `;`
+fn parse_assignment(input: Tokens) -> NomResult<Statement> {
+    let (input, (name, start)) = identifier(input)?;
+    let (input, op) = cut(alt((
+        token(Token::Equal).map(|_| AssignOp::Set),
+        token(Token::PlusEqual).map(|_| AssignOp::Add),
+    )))
+    .parse(input)?;
+    let (input, value) = cut(parse_expression).parse(input)?;
+    let span = start.merge(value.span);
+    Ok((input, Statement::new(StatementKind::Assign { name, op, value }, span)))
 }
 
 // ---------------------------------------------
 // Funções de Expression
 // ---------------------------------------------
 
-fn parse_expression(input: &str) -> IResult<&str, Expression> {
-    let (input, left) = parse_term(input)?;
-    // many0 de pares (op, termo)
+/// Uma função por nível de [`BinaryOp::precedence`], do mais fraco ao mais forte.
+fn parse_expression(input: Tokens) -> NomResult<Expression> {
+    parse_binary_level(input, parse_and, |token| match token {
+        Token::LogicalOr => Some(BinaryOp::Or),
+        _ => None,
+    })
+}
+
+fn parse_and(input: Tokens) -> NomResult<Expression> {
+    parse_binary_level(input, parse_comparison, |token| match token {
+        Token::LogicalAnd => Some(BinaryOp::And),
+        _ => None,
+    })
+}
+
+fn parse_comparison(input: Tokens) -> NomResult<Expression> {
+    let (input, left) = parse_sum(input)?;
+    let (input, rest) = opt((
+        map_opt(any_token, |t| match t.value {
+            Token::EqualEqual => Some(BinaryOp::Equal),
+            Token::NotEqual => Some(BinaryOp::NotEqual),
+            Token::Less => Some(BinaryOp::Less),
+            Token::LessEqual => Some(BinaryOp::LessEqual),
+            Token::Greater => Some(BinaryOp::Greater),
+            Token::GreaterEqual => Some(BinaryOp::GreaterEqual),
+            _ => None,
+        }),
+        cut(parse_sum),
+    ))
+    .parse(input)?;
+    let expr = match rest {
+        Some((op, right)) => Expression::binary(left, op, right),
+        None => left,
+    };
+    Ok((input, expr))
+}
+
+fn parse_sum(input: Tokens) -> NomResult<Expression> {
+    parse_binary_level(input, parse_product, |token| match token {
+        Token::Plus => Some(BinaryOp::Plus),
+        Token::Minus => Some(BinaryOp::Minus),
+        _ => None,
+    })
+}
+
+fn parse_product(input: Tokens) -> NomResult<Expression> {
+    parse_binary_level(input, parse_unary, |token| match token {
+        Token::Asterisk => Some(BinaryOp::Multiply),
+        Token::Slash => Some(BinaryOp::Divide),
+        _ => None,
+    })
+}
+
+/// Nível de operadores binários associativos à esquerda: operando (op operando)*
+fn parse_binary_level<'a>(
+    input: Tokens<'a>,
+    operand: fn(Tokens<'a>) -> NomResult<'a, Expression>,
+    operator: fn(&Token) -> Option<BinaryOp>,
+) -> NomResult<'a, Expression> {
+    let (input, left) = operand(input)?;
+    // many0 de pares (op, operando)
     let (input, exprs) = many0((
-        preceded(multispace0, alt((tag("+"), tag("-")))),
-        preceded(multispace0, parse_term),
-    ))(input)?;
-
-    let expr = exprs.into_iter().fold(left, |acc, (op, right)| {
-        let op_enum = if op == "+" { BinaryOp::Plus } else { BinaryOp::Minus };
-        Expression::Binary {
-            left: Box::new(acc),
-            op: op_enum,
-            right: Box::new(right),
-        }
-    });
+        map_opt(any_token, |t| operator(&t.value)),
+        cut(operand),
+    ))
+    .parse(input)?;
+
+    let expr = exprs
+        .into_iter()
+        .fold(left, |acc, (op, right)| Expression::binary(acc, op, right));
     Ok((input, expr))
 }
 
-fn parse_term(input: &str) -> IResult<&str, Expression> {
+fn parse_unary(input: Tokens) -> NomResult<Expression> {
     alt((
-        // Número
-        map_res(digit1, |digits: &str| digits.parse::<i64>().map(Expression::Number)),
-        // Identificador
-        map(take_while1(|c: char| c.is_alphabetic()), |s: &str| {
-            Expression::Identifier(s.to_string())
+        (
+            map_opt(any_token, |t| match t.value {
+                Token::LogicalNot => Some((UnaryOp::Not, t.span)),
+                Token::Minus => Some((UnaryOp::Negate, t.span)),
+                _ => None,
+            }),
+            cut(parse_unary),
+        )
+            .map(|((op, op_span), operand)| Expression::unary(op, op_span, operand)),
+        parse_term,
+    ))
+    .parse(input)
+}
+
+fn parse_term(input: Tokens) -> NomResult<Expression> {
+    let result = alt((
+        // Número ou identificador
+        map_opt(any_token, |t| {
+            let kind = match &t.value {
+                Token::Number(n) => ExpressionKind::Number(*n),
+                Token::Identifier(id) => ExpressionKind::Identifier(id.clone()),
+                _ => return None,
+            };
+            Some(Expression::new(kind, t.span))
         }),
         // ( expr )
-        delimited(char('('), parse_expression, char(')'))
-    ))(input)
+        delimited(token(Token::LParen), parse_expression, cut(token(Token::RParen))),
+    ))
+    .parse(input);
+    expecting(result, Expected::Expression)
+}
+
+/// Parseia os tokens com os combinadores do nom.
+pub fn parse(tokens: Vec<Spanned<Token>>) -> Result<Vec<Statement>, Vec<ParseError>> {
+    // Comentários são trivia: não chegam aos combinadores
+    let tokens = strip_comments(tokens);
+    let error = match parse_program(Tokens::new(&tokens)) {
+        Ok((_, statements)) => return Ok(statements),
+        Err(nom::Err::Error(err)) | Err(nom::Err::Failure(err)) => match err.input.first() {
+            Some(token) => ParseError::UnexpectedToken {
+                expected: err.expected,
+                found: token.value.clone(),
+                span: token.span,
+                position: token.position,
+            },
+            None => end_of_input(&tokens, err.expected),
+        },
+        Err(nom::Err::Incomplete(_)) => end_of_input(&tokens, Vec::new()),
+    };
+    Err(vec![error])
 }
 
-pub fn parse(tokens: Vec<String>) -> Result<(Vec<String>, String), String> {
-    if tokens.is_empty() {
-        Err("No tokens to parse".to_string())
-    } else {
-        Ok((vec![], format!("AST (Nom): {}", tokens.join(" "))))
+/// Erro de fim de entrada, num trecho vazio logo após o último token.
+fn end_of_input(tokens: &[Spanned<Token>], expected: Vec<Expected>) -> ParseError {
+    let end = tokens.last().map_or(0, |t| t.span.end);
+    ParseError::UnexpectedEof {
+        expected,
+        span: Span::new(end, end),
+        position: tokens.last().map(Spanned::end_position).unwrap_or_default(),
     }
 }
-*/
\ No newline at end of file
@@ -0,0 +1,68 @@
+use crate::span::Spanned;
+use crate::token::Token;
+use nom::{Input, Needed};
+use std::iter::Enumerate;
+use std::slice::Iter;
+
+/// Fatia de tokens usada como entrada pelos combinadores do nom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tokens<'a> {
+    pub tokens: &'a [Spanned<Token>],
+}
+
+impl<'a> Tokens<'a> {
+    pub fn new(tokens: &'a [Spanned<Token>]) -> Self {
+        Tokens { tokens }
+    }
+
+    /// Primeiro token da entrada, se houver.
+    pub fn first(&self) -> Option<&'a Spanned<Token>> {
+        self.tokens.first()
+    }
+}
+
+impl<'a> Input for Tokens<'a> {
+    type Item = &'a Spanned<Token>;
+    type Iter = Iter<'a, Spanned<Token>>;
+    type IterIndices = Enumerate<Self::Iter>;
+
+    fn input_len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    fn take(&self, index: usize) -> Self {
+        Tokens::new(&self.tokens[..index])
+    }
+
+    fn take_from(&self, index: usize) -> Self {
+        Tokens::new(&self.tokens[index..])
+    }
+
+    fn take_split(&self, index: usize) -> (Self, Self) {
+        let (prefix, suffix) = self.tokens.split_at(index);
+        (Tokens::new(suffix), Tokens::new(prefix))
+    }
+
+    fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        self.tokens.iter().position(predicate)
+    }
+
+    fn iter_elements(&self) -> Self::Iter {
+        self.tokens.iter()
+    }
+
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.tokens.iter().enumerate()
+    }
+
+    fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+        if self.tokens.len() >= count {
+            Ok(count)
+        } else {
+            Err(Needed::new(count - self.tokens.len()))
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use quest_lang::diagnostic;
+use quest_lang::error::QuestError;
+use quest_lang::formatter;
+use quest_lang::interpreter;
+use quest_lang::lexer_manual::lexer_impl;
+use quest_lang::parser_manual::parser_impl;
+use quest_lang::sensor::Game;
+use quest_lang::world::World;
+
+const USAGE: &str = "usage: quest fmt [--check] [file.quest...]
+       quest run <program.quest> <map>
+
+  fmt           format the files in place (no files: stdin -> stdout)
+  fmt --check   only check; fail if any file is not formatted
+  run           run the program on the map and print the final map";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("fmt") => fmt(&args[1..]),
+        Some("run") if args.len() == 3 => run(&args[1], &args[2]),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn fmt(args: &[String]) -> ExitCode {
+    let check = args.iter().any(|a| a == "--check");
+    let files: Vec<&String> = args.iter().filter(|a| *a != "--check").collect();
+
+    if files.is_empty() {
+        let mut source = String::new();
+        if let Err(err) = io::stdin().read_to_string(&mut source) {
+            eprintln!("error reading standard input: {}", err);
+            return ExitCode::from(2);
+        }
+        return match formatter::format(&source) {
+            Ok(formatted) if check && formatted != source => {
+                eprintln!("<stdin> is not formatted");
+                ExitCode::FAILURE
+            }
+            Ok(_) if check => ExitCode::SUCCESS,
+            Ok(formatted) => {
+                print!("{}", formatted);
+                ExitCode::SUCCESS
+            }
+            Err(errors) => {
+                diagnostic::emit(&errors, &source);
+                ExitCode::from(2)
+            }
+        };
+    }
+
+    let mut failed = false;
+    for path in files {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("error reading {}: {}", path, err);
+                failed = true;
+                continue;
+            }
+        };
+        let formatted = match formatter::format(&source) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                eprintln!("{}:", path);
+                diagnostic::emit(&errors, &source);
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        if check {
+            println!("{} is not formatted", path);
+            failed = true;
+        } else if let Err(err) = fs::write(path, formatted) {
+            eprintln!("error writing {}: {}", path, err);
+            failed = true;
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run(program_path: &str, map_path: &str) -> ExitCode {
+    let read = |path: &str| {
+        fs::read_to_string(path).map_err(|err| eprintln!("error reading {}: {}", path, err))
+    };
+    let (Ok(source), Ok(map)) = (read(program_path), read(map_path)) else {
+        return ExitCode::from(2);
+    };
+    let mut game = match map.parse::<World>() {
+        Ok(world) => Game::new(world),
+        Err(err) => {
+            eprintln!("{}: {}", map_path, err);
+            return ExitCode::from(2);
+        }
+    };
+    let program = lexer_impl::lex(&source)
+        .map_err(|errors| errors.into_iter().map(QuestError::from).collect::<Vec<_>>())
+        .and_then(|tokens| {
+            parser_impl::parse(tokens)
+                .map_err(|errors| errors.into_iter().map(QuestError::from).collect())
+        });
+    let program = match program {
+        Ok(program) => program,
+        Err(errors) => {
+            diagnostic::emit(&errors, &source);
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = interpreter::run(&program, &mut game);
+    let world = &game.world;
+    print!("{}", world);
+    println!("health: {}  treasure: {}", world.hero.health, world.treasure);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            diagnostic::emit(&[err], &source);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::ast::Command;
+use crate::interpreter::GameState;
+use crate::world::{Facing, Tile, World};
+
+/// Função que lê um valor do mundo.
+pub type SensorFn = dyn Fn(&World) -> i64;
+
+/// Identificadores que os programas podem ler, cada um ligado a um sensor.
+///
+/// `Sensors::default()` traz os sensores embutidos:
+///
+/// - `hero`: vida do herói;
+/// - `enemy`: distância, em passos, até o inimigo mais próximo (0 se não
+///   houver inimigos);
+/// - `treasure`: 1 se houver tesouro numa casa vizinha, senão 0.
+pub struct Sensors {
+    sensors: Vec<(String, Box<SensorFn>)>,
+}
+
+impl Default for Sensors {
+    fn default() -> Self {
+        let mut sensors = Sensors::empty();
+        sensors
+            .register("hero", hero)
+            .register("enemy", enemy)
+            .register("treasure", treasure);
+        sensors
+    }
+}
+
+impl Sensors {
+    /// Registro sem nenhum sensor, nem os embutidos.
+    pub fn empty() -> Self {
+        Sensors {
+            sensors: Vec::new(),
+        }
+    }
+
+    /// Registra um sensor, substituindo o que já tiver o mesmo nome.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        sensor: impl Fn(&World) -> i64 + 'static,
+    ) -> &mut Self {
+        let name = name.into();
+        let sensor: Box<SensorFn> = Box::new(sensor);
+        match self.sensors.iter_mut().find(|(existing, _)| *existing == name) {
+            Some(entry) => entry.1 = sensor,
+            None => self.sensors.push((name, sensor)),
+        }
+        self
+    }
+
+    /// Valor do sensor `name` no mundo dado, se ele existir.
+    pub fn read(&self, name: &str, world: &World) -> Option<i64> {
+        self.sensors
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, sensor)| sensor(world))
+    }
+
+    /// Nomes registrados, na ordem de registro.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sensors.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+impl std::fmt::Debug for Sensors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// Vida do herói.
+pub fn hero(world: &World) -> i64 {
+    world.hero.health
+}
+
+/// Distância até o inimigo mais próximo, sem diagonais e ignorando paredes;
+/// 0 se não houver inimigos.
+pub fn enemy(world: &World) -> i64 {
+    world
+        .find(Tile::Enemy)
+        .map(|enemy| enemy.manhattan(world.hero.position) as i64)
+        .min()
+        .unwrap_or(0)
+}
+
+/// 1 se alguma casa vizinha ao herói tiver tesouro.
+pub fn treasure(world: &World) -> i64 {
+    let position = world.hero.position;
+    [Facing::Up, Facing::Down, Facing::Left, Facing::Right]
+        .into_iter()
+        .filter_map(|facing| position.step(facing, 1))
+        .any(|point| world.tile(point) == Tile::Treasure) as i64
+}
+
+/// Mundo com os sensores que o programa enxerga: o estado que o
+/// interpretador recebe.
+#[derive(Debug)]
+pub struct Game {
+    pub world: World,
+    pub sensors: Sensors,
+}
+
+impl Game {
+    /// Jogo com os sensores embutidos.
+    pub fn new(world: World) -> Self {
+        Game {
+            world,
+            sensors: Sensors::default(),
+        }
+    }
+}
+
+impl GameState for Game {
+    fn execute(&mut self, command: Command) -> Result<(), String> {
+        self.world.apply(command)
+    }
+
+    fn read(&mut self, name: &str) -> Option<i64> {
+        self.sensors.read(name, &self.world)
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+use std::ops::Range;
+
+/// Intervalo de bytes `[start, end)` no código-fonte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// Menor intervalo que cobre `self` e `other`.
+    pub fn merge(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        Span::new(range.start, range.end)
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
+
+/// Linha e coluna (ambas a partir de 1). A coluna conta caracteres, não bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// Calcula a posição de um deslocamento em bytes dentro de `source`.
+    pub fn of(source: &str, offset: usize) -> Position {
+        let offset = offset.min(source.len());
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position { line: 1, column: 1 }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Valor acompanhado do trecho do código de onde veio.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+    pub position: Position,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, span: Span, position: Position) -> Self {
+        Spanned { value, span, position }
+    }
+
+    /// Posição logo após o trecho. Supõe um trecho numa linha só e sem
+    /// caracteres de mais de um byte, como todos os tokens fora os comentários.
+    pub fn end_position(&self) -> Position {
+        Position {
+            line: self.position.line,
+            column: self.position.column + (self.span.end - self.span.start),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+#[cfg(any(feature = "logos-lexer", feature = "manual-lexer"))]
+use crate::error::LexError;
+#[cfg(feature = "logos-lexer")]
+use crate::error::LexErrorKind;
+#[cfg(any(feature = "logos-lexer", feature = "manual-lexer"))]
+use crate::span::Spanned;
+#[cfg(feature = "logos-lexer")]
+use logos::Logos;
+use std::fmt;
+use std::hash::Hash;
+
+/// Token da QuestLang, produzido tanto pelo lexer Logos quanto pelo manual
+/// e aceito por todos os parsers. As regras do Logos só existem com a
+/// feature `logos-lexer`.
+#[cfg_attr(feature = "logos-lexer", derive(Logos))] // Para o Lexer
+#[derive(
+    Debug,     // Para imprimir
+    PartialEq, // Necessário para comparações
+    Eq,        // Necessário se for usar Hash
+    Clone,     // Necessário se for clonar tokens
+    Hash       // Necessário para certos combinadores do Chumsky
+)]
+#[cfg_attr(feature = "logos-lexer", logos(error = LexErrorKind))]
+// Ignora espaços em branco e outros separadores.
+#[cfg_attr(feature = "logos-lexer", logos(skip r"[ \t\r\n\f]+"))]
+pub enum Token {
+    // Comandos de Movimento
+    #[cfg_attr(feature = "logos-lexer", token("move_up"))]
+    MoveUp,
+    #[cfg_attr(feature = "logos-lexer", token("move_down"))]
+    MoveDown,
+    #[cfg_attr(feature = "logos-lexer", token("move_left"))]
+    MoveLeft,
+    #[cfg_attr(feature = "logos-lexer", token("move_right"))]
+    MoveRight,
+
+    // Comandos de Ação
+    #[cfg_attr(feature = "logos-lexer", token("jump"))]
+    Jump,
+    #[cfg_attr(feature = "logos-lexer", token("attack"))]
+    Attack,
+    #[cfg_attr(feature = "logos-lexer", token("defend"))]
+    Defend,
+
+    // Estruturas de Controle
+    #[cfg_attr(feature = "logos-lexer", token("if"))]
+    If,
+    #[cfg_attr(feature = "logos-lexer", token("else"))]
+    Else,
+    #[cfg_attr(feature = "logos-lexer", token("while"))]
+    While,
+    #[cfg_attr(feature = "logos-lexer", token("for"))]
+    For,
+    #[cfg_attr(feature = "logos-lexer", token("repeat"))]
+    Repeat,
+
+    // Variáveis
+    #[cfg_attr(feature = "logos-lexer", token("let"))]
+    Let,
+
+    // Operadores Aritméticos
+    #[cfg_attr(feature = "logos-lexer", token("+"))]
+    Plus,
+    #[cfg_attr(feature = "logos-lexer", token("-"))]
+    Minus,
+    #[cfg_attr(feature = "logos-lexer", token("*"))]
+    Asterisk,
+    #[cfg_attr(feature = "logos-lexer", token("/"))]
+    Slash,
+
+    // Operadores Lógicos
+    #[cfg_attr(feature = "logos-lexer", token("&&"))]
+    #[cfg_attr(feature = "logos-lexer", token("&", incomplete_operator))]
+    LogicalAnd,
+    #[cfg_attr(feature = "logos-lexer", token("||"))]
+    #[cfg_attr(feature = "logos-lexer", token("|", incomplete_operator))]
+    LogicalOr,
+    #[cfg_attr(feature = "logos-lexer", token("!"))]
+    LogicalNot,
+
+    // Operadores de Comparação
+    #[cfg_attr(feature = "logos-lexer", token("=="))]
+    EqualEqual,
+    #[cfg_attr(feature = "logos-lexer", token("!="))]
+    NotEqual,
+    #[cfg_attr(feature = "logos-lexer", token("<"))]
+    Less,
+    #[cfg_attr(feature = "logos-lexer", token("<="))]
+    LessEqual,
+    #[cfg_attr(feature = "logos-lexer", token(">"))]
+    Greater,
+    #[cfg_attr(feature = "logos-lexer", token(">="))]
+    GreaterEqual,
+
+    // Atribuição
+    #[cfg_attr(feature = "logos-lexer", token("="))]
+    Equal,
+    #[cfg_attr(feature = "logos-lexer", token("+="))]
+    PlusEqual,
+
+    // Delimitadores
+    #[cfg_attr(feature = "logos-lexer", token("("))]
+    LParen,
+    #[cfg_attr(feature = "logos-lexer", token(")"))]
+    RParen,
+    #[cfg_attr(feature = "logos-lexer", token("{"))]
+    LBrace,
+    #[cfg_attr(feature = "logos-lexer", token("}"))]
+    RBrace,
+    #[cfg_attr(feature = "logos-lexer", token(";"))]
+    Semicolon,
+
+    // Literais e Identificadores
+    #[cfg_attr(feature = "logos-lexer", regex("[0-9]+", |lex| lex.slice().parse::<i64>().map_err(|_| LexErrorKind::InvalidNumber)))]
+    Number(i64),
+    #[cfg_attr(feature = "logos-lexer", regex("[a-zA-Z_][a-zA-Z0-9_]*", |lex| lex.slice().to_string()))]
+    Identifier(String),
+
+    // Comentário
+    #[cfg_attr(feature = "logos-lexer", regex("//[^\n]*", |lex| lex.slice().to_string()))]
+    Comment(String),
+}
+
+impl fmt::Display for Token {
+    /// Escreve o token como aparece no código-fonte.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Token::MoveUp => "move_up",
+            Token::MoveDown => "move_down",
+            Token::MoveLeft => "move_left",
+            Token::MoveRight => "move_right",
+            Token::Jump => "jump",
+            Token::Attack => "attack",
+            Token::Defend => "defend",
+            Token::If => "if",
+            Token::Else => "else",
+            Token::While => "while",
+            Token::For => "for",
+            Token::Repeat => "repeat",
+            Token::Let => "let",
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Asterisk => "*",
+            Token::Slash => "/",
+            Token::LogicalAnd => "&&",
+            Token::LogicalOr => "||",
+            Token::LogicalNot => "!",
+            Token::EqualEqual => "==",
+            Token::NotEqual => "!=",
+            Token::Less => "<",
+            Token::LessEqual => "<=",
+            Token::Greater => ">",
+            Token::GreaterEqual => ">=",
+            Token::Equal => "=",
+            Token::PlusEqual => "+=",
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::LBrace => "{",
+            Token::RBrace => "}",
+            Token::Semicolon => ";",
+            Token::Number(n) => return write!(f, "{}", n),
+            Token::Identifier(name) => name,
+            Token::Comment(text) => text,
+        };
+        f.write_str(text)
+    }
+}
+
+/// `&` ou `|` sozinhos não formam operador; o lexer reporta o erro.
+#[cfg(feature = "logos-lexer")]
+fn incomplete_operator(_: &mut logos::Lexer<Token>) -> Result<(), LexErrorKind> {
+    Err(LexErrorKind::IncompleteOperator)
+}
+
+/// Reúne a saída de um lexer, acumulando todos os erros léxicos encontrados.
+#[cfg(any(feature = "logos-lexer", feature = "manual-lexer"))]
+pub(crate) fn collect_tokens<I>(lexer: I) -> Result<Vec<Spanned<Token>>, Vec<LexError>>
+where
+    I: Iterator<Item = Result<Spanned<Token>, LexError>>,
+{
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    for result in lexer {
+        match result {
+            Ok(token) => tokens.push(token),
+            Err(error) => errors.push(error),
+        }
+    }
+    if errors.is_empty() {
+        Ok(tokens)
+    } else {
+        Err(errors)
+    }
+}
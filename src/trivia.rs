@@ -0,0 +1,82 @@
+use crate::ast::{Statement, StatementKind};
+use crate::span::{Position, Span, Spanned};
+use crate::token::Token;
+
+/// Comentário do código-fonte. Não faz parte da AST: os parsers o pulam,
+/// mas ele fica disponível para ferramentas como o formatador.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Comment {
+    /// Texto completo, incluindo o `//`.
+    pub text: String,
+    pub span: Span,
+    pub position: Position,
+}
+
+/// Separa os comentários dos tokens que os parsers consomem.
+pub fn split_comments(tokens: Vec<Spanned<Token>>) -> (Vec<Spanned<Token>>, Vec<Comment>) {
+    let mut code = Vec::with_capacity(tokens.len());
+    let mut comments = Vec::new();
+    for token in tokens {
+        match token.value {
+            Token::Comment(text) => comments.push(Comment {
+                text,
+                span: token.span,
+                position: token.position,
+            }),
+            _ => code.push(token),
+        }
+    }
+    (code, comments)
+}
+
+/// Descarta os comentários, mantendo só os tokens significativos.
+pub fn strip_comments(mut tokens: Vec<Spanned<Token>>) -> Vec<Spanned<Token>> {
+    tokens.retain(|t| !matches!(t.value, Token::Comment(_)));
+    tokens
+}
+
+/// Statement ao qual o comentário se refere: o mais interno que começa logo
+/// depois dele ou, se não houver nenhum no mesmo bloco, o último antes dele.
+pub fn nearest_statement<'a>(statements: &'a [Statement], comment: &Comment) -> Option<&'a Statement> {
+    nearest_in(statements.iter(), comment)
+}
+
+fn nearest_in<'a, I>(statements: I, comment: &Comment) -> Option<&'a Statement>
+where
+    I: IntoIterator<Item = &'a Statement>,
+{
+    let mut previous = None;
+    for statement in statements {
+        if statement.span.start >= comment.span.end {
+            return Some(statement);
+        }
+        if statement.span.end > comment.span.start {
+            // O comentário está dentro deste statement
+            return nearest_in(children(statement), comment).or(Some(statement));
+        }
+        previous = Some(statement);
+    }
+    previous
+}
+
+/// Statements aninhados diretamente, na ordem do código.
+fn children(statement: &Statement) -> Vec<&Statement> {
+    match &statement.kind {
+        StatementKind::Block(statements) => statements.iter().collect(),
+        StatementKind::If {
+            then_branch,
+            else_branch,
+            ..
+        } => std::iter::once(then_branch.as_ref())
+            .chain(else_branch.as_deref())
+            .collect(),
+        StatementKind::While { body, .. } | StatementKind::Repeat { body, .. } => vec![body],
+        StatementKind::For {
+            init, update, body, ..
+        } => vec![init, update, body],
+        StatementKind::Command(_)
+        | StatementKind::Let { .. }
+        | StatementKind::Assign { .. }
+        | StatementKind::Error => Vec::new(),
+    }
+}
@@ -0,0 +1,451 @@
+use crate::ast::Command;
+use std::fmt;
+use std::str::FromStr;
+
+/// Vida inicial do herói.
+pub const MAX_HEALTH: i64 = 10;
+
+/// Conteúdo de uma casa do mapa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Floor,
+    Wall,
+    Pit,
+    Enemy,
+    Treasure,
+}
+
+/// Para onde o herói está olhando.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Facing {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Facing {
+    /// Deslocamento (coluna, linha) de um passo nesta direção.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Facing::Up => (0, -1),
+            Facing::Down => (0, 1),
+            Facing::Left => (-1, 0),
+            Facing::Right => (1, 0),
+        }
+    }
+}
+
+/// Casa do mapa: `x` é a coluna e `y` a linha, ambas a partir de 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Self {
+        Point { x, y }
+    }
+
+    /// Casa a `steps` passos na direção dada, se não sair do lado negativo.
+    pub fn step(self, facing: Facing, steps: usize) -> Option<Point> {
+        let (dx, dy) = facing.delta();
+        let x = self.x.checked_add_signed(dx * steps as isize)?;
+        let y = self.y.checked_add_signed(dy * steps as isize)?;
+        Some(Point { x, y })
+    }
+
+    /// Distância em passos, sem diagonais.
+    pub fn manhattan(self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hero {
+    pub position: Point,
+    pub facing: Facing,
+    pub health: i64,
+    /// Defendeu neste turno: os inimigos vizinhos não causam dano.
+    pub defending: bool,
+}
+
+/// O que um caractere do mapa representa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cell {
+    Tile(Tile),
+    /// O herói, olhando na direção dada, sobre o chão.
+    Hero(Facing),
+}
+
+impl Cell {
+    /// Todos os tipos de casa, na ordem em que a legenda é impressa.
+    pub const ALL: [Cell; 9] = [
+        Cell::Tile(Tile::Wall),
+        Cell::Tile(Tile::Floor),
+        Cell::Tile(Tile::Pit),
+        Cell::Tile(Tile::Enemy),
+        Cell::Tile(Tile::Treasure),
+        Cell::Hero(Facing::Up),
+        Cell::Hero(Facing::Down),
+        Cell::Hero(Facing::Left),
+        Cell::Hero(Facing::Right),
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Cell::Tile(Tile::Wall) => "wall",
+            Cell::Tile(Tile::Floor) => "floor",
+            Cell::Tile(Tile::Pit) => "pit",
+            Cell::Tile(Tile::Enemy) => "enemy",
+            Cell::Tile(Tile::Treasure) => "treasure",
+            Cell::Hero(Facing::Up) => "hero up",
+            Cell::Hero(Facing::Down) => "hero down",
+            Cell::Hero(Facing::Left) => "hero left",
+            Cell::Hero(Facing::Right) => "hero right",
+        }
+    }
+
+    fn default_char(self) -> char {
+        match self {
+            Cell::Tile(Tile::Wall) => '#',
+            Cell::Tile(Tile::Floor) => '.',
+            Cell::Tile(Tile::Pit) => 'O',
+            Cell::Tile(Tile::Enemy) => 'E',
+            Cell::Tile(Tile::Treasure) => '$',
+            Cell::Hero(Facing::Up) => '^',
+            Cell::Hero(Facing::Down) => 'v',
+            Cell::Hero(Facing::Left) => '<',
+            Cell::Hero(Facing::Right) => '>',
+        }
+    }
+}
+
+/// Caractere de cada tipo de casa. Cada tipo tem exatamente um caractere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Legend {
+    chars: [char; 9],
+}
+
+impl Default for Legend {
+    fn default() -> Self {
+        Legend {
+            chars: Cell::ALL.map(Cell::default_char),
+        }
+    }
+}
+
+impl Legend {
+    pub fn char_of(&self, cell: Cell) -> char {
+        self.chars[Self::index(cell)]
+    }
+
+    pub fn cell_of(&self, c: char) -> Option<Cell> {
+        Cell::ALL.into_iter().find(|&cell| self.char_of(cell) == c)
+    }
+
+    pub fn set(&mut self, cell: Cell, c: char) {
+        self.chars[Self::index(cell)] = c;
+    }
+
+    fn index(cell: Cell) -> usize {
+        Cell::ALL.iter().position(|&other| other == cell).unwrap()
+    }
+}
+
+/// Erro ao carregar um mapa. Linhas contam a partir de 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapError {
+    /// Linha da legenda fora do formato `X = nome`, ou com um caractere já
+    /// usado por outro tipo de casa.
+    InvalidLegend { line: usize, text: String },
+    /// Caractere do mapa que não está na legenda.
+    UnknownTile { c: char, line: usize, column: usize },
+    /// Linha do mapa com largura diferente da primeira.
+    RaggedRow { line: usize },
+    EmptyMap,
+    MissingHero,
+    MultipleHeroes { line: usize, column: usize },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::InvalidLegend { line, text } => {
+                write!(f, "invalid legend entry `{}` at line {}", text, line)
+            }
+            MapError::UnknownTile { c, line, column } => {
+                write!(f, "unknown tile `{}` at {}:{}", c, line, column)
+            }
+            MapError::RaggedRow { line } => {
+                write!(f, "row at line {} has a different width", line)
+            }
+            MapError::EmptyMap => write!(f, "the map is empty"),
+            MapError::MissingHero => write!(f, "the map has no hero"),
+            MapError::MultipleHeroes { line, column } => {
+                write!(f, "second hero at {}:{}", line, column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+/// Mundo em grade onde o herói executa os comandos.
+///
+/// O texto do mapa tem, opcionalmente, uma legenda com linhas `X = nome`
+/// (nomes: `wall`, `floor`, `pit`, `enemy`, `treasure`, `hero up`,
+/// `hero down`, `hero left`, `hero right`) terminada por uma linha `---`.
+/// O resto é a grade, uma linha por fileira. Sem legenda, vale a padrão:
+///
+/// ```text
+/// # = wall        ^ v < > = herói olhando para cima, baixo, esquerda, direita
+/// . = floor       O = pit     E = enemy     $ = treasure
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct World {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+    pub hero: Hero,
+    /// Tesouros já coletados.
+    pub treasure: u32,
+    legend: Legend,
+}
+
+impl World {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn legend(&self) -> &Legend {
+        &self.legend
+    }
+
+    /// Casa em `point`; fora do mapa tudo é parede.
+    pub fn tile(&self, point: Point) -> Tile {
+        if point.x < self.width && point.y < self.height {
+            self.tiles[point.y * self.width + point.x]
+        } else {
+            Tile::Wall
+        }
+    }
+
+    pub fn set_tile(&mut self, point: Point, tile: Tile) {
+        if point.x < self.width && point.y < self.height {
+            self.tiles[point.y * self.width + point.x] = tile;
+        }
+    }
+
+    /// Todas as casas com o conteúdo dado, linha por linha.
+    pub fn find(&self, tile: Tile) -> impl Iterator<Item = Point> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(move |(_, &t)| t == tile)
+            .map(|(i, _)| Point::new(i % self.width, i / self.width))
+    }
+
+    /// Casa logo à frente do herói.
+    pub fn ahead(&self) -> Option<Point> {
+        self.hero.position.step(self.hero.facing, 1)
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.hero.health > 0
+    }
+
+    /// Executa um comando do herói e depois o turno dos inimigos.
+    ///
+    /// Movimentos viram o herói para a direção e andam uma casa, se ela não
+    /// for parede nem inimigo. `jump` anda duas casas para a frente, passando
+    /// por cima de buracos e inimigos. Cair num buraco encerra o jogo; pisar
+    /// num tesouro o coleta. `attack` derrota o inimigo à frente e `defend`
+    /// evita o dano deste turno. Ao fim, cada inimigo vizinho tira 1 de vida.
+    pub fn apply(&mut self, command: Command) -> Result<(), String> {
+        if !self.is_alive() {
+            return Err("the hero was defeated".to_string());
+        }
+        self.hero.defending = false;
+        match command {
+            Command::MoveUp => self.walk(Facing::Up)?,
+            Command::MoveDown => self.walk(Facing::Down)?,
+            Command::MoveLeft => self.walk(Facing::Left)?,
+            Command::MoveRight => self.walk(Facing::Right)?,
+            Command::Jump => {
+                let over = self.hero.position.step(self.hero.facing, 1);
+                let landing = self.hero.position.step(self.hero.facing, 2);
+                if let (Some(over), Some(landing)) = (over, landing) {
+                    if self.tile(over) != Tile::Wall && self.can_enter(landing) {
+                        self.enter(landing)?;
+                    }
+                }
+            }
+            Command::Attack => {
+                if let Some(target) = self.ahead().filter(|&p| self.tile(p) == Tile::Enemy) {
+                    self.set_tile(target, Tile::Floor);
+                }
+            }
+            Command::Defend => self.hero.defending = true,
+        }
+        self.enemies_turn()
+    }
+
+    fn walk(&mut self, facing: Facing) -> Result<(), String> {
+        self.hero.facing = facing;
+        match self.ahead() {
+            Some(target) if self.can_enter(target) => self.enter(target),
+            _ => Ok(()),
+        }
+    }
+
+    fn can_enter(&self, point: Point) -> bool {
+        !matches!(self.tile(point), Tile::Wall | Tile::Enemy)
+    }
+
+    fn enter(&mut self, point: Point) -> Result<(), String> {
+        self.hero.position = point;
+        match self.tile(point) {
+            Tile::Pit => {
+                self.hero.health = 0;
+                Err("the hero fell into a pit".to_string())
+            }
+            Tile::Treasure => {
+                self.set_tile(point, Tile::Floor);
+                self.treasure += 1;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn enemies_turn(&mut self) -> Result<(), String> {
+        if self.hero.defending {
+            return Ok(());
+        }
+        let position = self.hero.position;
+        let attackers = [Facing::Up, Facing::Down, Facing::Left, Facing::Right]
+            .into_iter()
+            .filter_map(|facing| position.step(facing, 1))
+            .filter(|&p| self.tile(p) == Tile::Enemy)
+            .count();
+        self.hero.health -= attackers as i64;
+        if self.is_alive() {
+            Ok(())
+        } else {
+            Err("the hero was defeated".to_string())
+        }
+    }
+}
+
+impl FromStr for World {
+    type Err = MapError;
+
+    fn from_str(text: &str) -> Result<Self, MapError> {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut legend = Legend::default();
+        // Com legenda, a grade começa depois da linha `---`
+        let grid_start = match lines.iter().position(|line| line.trim() == "---") {
+            Some(separator) => {
+                for (i, line) in lines[..separator].iter().enumerate() {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let invalid = || MapError::InvalidLegend {
+                        line: i + 1,
+                        text: line.to_string(),
+                    };
+                    let (c, name) = line.split_once('=').ok_or_else(invalid)?;
+                    let mut chars = c.trim().chars();
+                    let (Some(c), None) = (chars.next(), chars.next()) else {
+                        return Err(invalid());
+                    };
+                    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+                    let cell = Cell::ALL
+                        .into_iter()
+                        .find(|cell| cell.name() == name)
+                        .ok_or_else(invalid)?;
+                    // Um caractere só pode representar um tipo de casa
+                    if legend.cell_of(c).is_some_and(|other| other != cell) {
+                        return Err(invalid());
+                    }
+                    legend.set(cell, c);
+                }
+                separator + 1
+            }
+            None => 0,
+        };
+
+        let rows: Vec<(usize, &str)> = lines
+            .iter()
+            .enumerate()
+            .skip(grid_start)
+            .map(|(i, line)| (i + 1, line.trim_end()))
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+        let width = rows.first().ok_or(MapError::EmptyMap)?.1.chars().count();
+
+        let mut tiles = Vec::with_capacity(width * rows.len());
+        let mut hero = None;
+        for (y, &(line, row)) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(MapError::RaggedRow { line });
+            }
+            for (x, c) in row.chars().enumerate() {
+                match legend.cell_of(c) {
+                    Some(Cell::Tile(tile)) => tiles.push(tile),
+                    Some(Cell::Hero(facing)) => {
+                        if hero.is_some() {
+                            return Err(MapError::MultipleHeroes { line, column: x + 1 });
+                        }
+                        hero = Some(Hero {
+                            position: Point::new(x, y),
+                            facing,
+                            health: MAX_HEALTH,
+                            defending: false,
+                        });
+                        tiles.push(Tile::Floor);
+                    }
+                    None => return Err(MapError::UnknownTile { c, line, column: x + 1 }),
+                }
+            }
+        }
+
+        Ok(World {
+            width,
+            height: rows.len(),
+            tiles,
+            hero: hero.ok_or(MapError::MissingHero)?,
+            treasure: 0,
+            legend,
+        })
+    }
+}
+
+/// Imprime a legenda completa, `---` e a grade, no formato que `from_str` lê.
+impl fmt::Display for World {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cell in Cell::ALL {
+            writeln!(f, "{} = {}", self.legend.char_of(cell), cell.name())?;
+        }
+        writeln!(f, "---")?;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let point = Point::new(x, y);
+                let cell = if point == self.hero.position {
+                    Cell::Hero(self.hero.facing)
+                } else {
+                    Cell::Tile(self.tile(point))
+                };
+                write!(f, "{}", self.legend.char_of(cell))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
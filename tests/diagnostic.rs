@@ -0,0 +1,215 @@
+//! Texto dos erros sintáticos e dos diagnósticos renderizados: mensagem,
+//! posição, trecho sublinhado, conjunto esperado, rótulos e notas. Os erros
+//! sintáticos são conferidos em todos os parsers habilitados.
+
+use quest_lang::backend;
+use quest_lang::diagnostic::{render, Diagnostic};
+use quest_lang::error::{Expected, ParseError};
+use quest_lang::span::{Position, Span};
+use quest_lang::token::Token;
+
+/// Primeiro erro de cada parser habilitado e da CST.
+fn first_errors(source: &str) -> Vec<(&'static str, ParseError)> {
+    let Some(lexer) = backend::lexers().first() else {
+        return Vec::new();
+    };
+    let tokens = (lexer.lex)(source).unwrap();
+    backend::parsers()
+        .iter()
+        .map(|parser| (parser.name, (parser.parse)(tokens.clone()).unwrap_err().remove(0)))
+        .chain(cst_error(source))
+        .collect()
+}
+
+#[cfg(feature = "cst")]
+fn cst_error(source: &str) -> Option<(&'static str, ParseError)> {
+    use quest_lang::error::QuestError;
+
+    match quest_lang::cst::parser::parse(source).errors().first() {
+        Some(QuestError::Parse(error)) => Some(("CST", error.clone())),
+        other => panic!("CST: esperava um erro sintático, veio {:?}", other),
+    }
+}
+
+#[cfg(not(feature = "cst"))]
+fn cst_error(_source: &str) -> Option<(&'static str, ParseError)> {
+    None
+}
+
+#[test]
+fn parse_errors_report_line_and_column() {
+    // A tabulação conta uma coluna
+    let source = "// café\nif (hero) {\n\tjump 42\n}";
+    for (name, error) in first_errors(source) {
+        assert_eq!(error.position(), Position { line: 3, column: 7 }, "parser {}", name);
+        assert_eq!(
+            error.to_string(),
+            "unexpected number `42` at 3:7, expected a statement",
+            "parser {}",
+            name
+        );
+    }
+
+    // O fim da entrada fica logo após o último token, antes do comentário
+    let source = "while (1) {\n    jump // é\n";
+    for (name, error) in first_errors(source) {
+        assert_eq!(error.position(), Position { line: 2, column: 9 }, "parser {}", name);
+    }
+}
+
+/// Confere o primeiro erro de cada parser, renderizado sem cores.
+fn assert_rendered(source: &str, expected: &str) {
+    let Some(lexer) = backend::lexers().first() else {
+        return;
+    };
+    let tokens = (lexer.lex)(source).unwrap();
+    for parser in backend::parsers() {
+        let errors = (parser.parse)(tokens.clone()).unwrap_err();
+        let diagnostic: Diagnostic = (&errors[0]).into();
+        assert_eq!(render(&diagnostic, source, false), expected, "parser {}", parser.name);
+    }
+}
+
+#[test]
+fn missing_operands_expect_an_expression() {
+    assert_rendered(
+        "let x = ;",
+        "\
+error: unexpected `;`
+ --> 1:9
+  |
+1 | let x = ;
+  |         ^ expected an expression
+",
+    );
+}
+
+#[test]
+fn statement_errors_expect_a_statement() {
+    assert_rendered(
+        "jump\n42 attack",
+        "\
+error: unexpected number `42`
+ --> 2:1
+  |
+2 | 42 attack
+  | ^^ expected a statement
+",
+    );
+    assert_rendered(
+        "move_up\n}\nmove_down",
+        "\
+error: unexpected `}`
+ --> 2:1
+  |
+2 | }
+  | ^ expected a statement
+",
+    );
+}
+
+#[test]
+fn expected_sets_agree_across_parsers() {
+    // Os operadores que poderiam continuar a expressão não entram no conjunto
+    for (name, error) in first_errors("let x = 1") {
+        assert_eq!(
+            error.to_string(),
+            "unexpected end of input at 1:10, expected `;`",
+            "parser {}",
+            name
+        );
+    }
+    // Dentro de um bloco, o erro é o do statement, não o da `}`
+    for (name, error) in first_errors("{ == }") {
+        assert_eq!(
+            error.to_string(),
+            "unexpected `==` at 1:3, expected a statement",
+            "parser {}",
+            name
+        );
+    }
+}
+
+#[cfg(feature = "cst")]
+#[test]
+fn cst_reports_each_mistake_once() {
+    let errors = |source: &str| -> Vec<String> {
+        let parse = quest_lang::cst::parser::parse(source);
+        parse.errors().iter().map(ToString::to_string).collect()
+    };
+    // Sem o operador de atribuição, o resto do statement não gera mais erros
+    assert_eq!(errors("a < b < c"), ["unexpected `<` at 1:3, expected one of `=`, `+=`"]);
+    assert_eq!(
+        errors("a b c d;\njump"),
+        ["unexpected identifier `b` at 1:3, expected one of `=`, `+=`"]
+    );
+    // Dois erros no mesmo token viram um só
+    assert_eq!(
+        errors("let x = 1 == 2 == 3;"),
+        ["unexpected `==` at 1:16, expected `;`"]
+    );
+}
+
+#[test]
+fn underlines_keep_the_tabs_of_the_line() {
+    assert_rendered(
+        "if (hero)\n\tjump x",
+        "\
+error: unexpected end of input
+ --> 2:8
+  |
+2 | \tjump x
+  | \t      ^ expected one of `+=`, `=`
+",
+    );
+}
+
+#[test]
+fn unclosed_delimiters_point_at_the_opening() {
+    let source = "while (1) {\n    jump";
+    let error = ParseError::UnclosedDelimiter {
+        delimiter: Token::LBrace,
+        open: Span::new(10, 11),
+        open_position: Position { line: 1, column: 11 },
+        expected: vec![Expected::Token(Token::RBrace)],
+        span: Span::new(20, 20),
+        position: Position { line: 2, column: 9 },
+    };
+    assert_eq!(error.to_string(), "unclosed delimiter `{` opened at 1:11, expected `}`");
+    assert_eq!(
+        render(&(&error).into(), source, false),
+        "\
+error: unclosed delimiter `{`
+ --> 2:9
+  |
+1 | while (1) {
+  |           - unclosed delimiter
+2 |     jump
+  |         ^ expected `}`
+"
+    );
+}
+
+#[test]
+fn lex_errors_carry_labels_and_notes() {
+    let Some(lexer) = backend::lexers().first() else {
+        return;
+    };
+    let source = "x & y";
+    let errors = (lexer.lex)(source).unwrap_err();
+    let diagnostic: Diagnostic = (&errors[0]).into();
+    assert_eq!(
+        render(&diagnostic, source, false),
+        "\
+error: unexpected character `&`
+ --> 1:3
+  |
+1 | x & y
+  |   ^ did you mean `&&`?
+  = note: the logical operators are `&&` and `||`
+"
+    );
+    let colored = render(&diagnostic, source, true);
+    assert!(colored.starts_with("\x1b[1;31merror\x1b[0m"));
+    assert!(colored.contains("\x1b[1;31m^ did you mean `&&`?\x1b[0m"));
+}
@@ -0,0 +1,350 @@
+//! Testes diferenciais: roda todos os lexers e parsers habilitados sobre o
+//! corpus em `tests/corpus` e exige que concordem.
+//!
+//! - `valid/`: todos os backends aceitam e produzem a mesma AST normalizada;
+//! - `invalid/`: todos os backends rejeitam.
+//!
+//! As divergências aparecem como diffs das ASTs normalizadas.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use quest_lang::ast::{Expression, ExpressionKind, Statement, StatementKind};
+use quest_lang::backend::{self, ParserBackend};
+use quest_lang::span::Spanned;
+use quest_lang::token::Token;
+use similar::TextDiff;
+
+fn corpus(kind: &str) -> Vec<(PathBuf, String)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus").join(kind);
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("não foi possível ler {}: {}", dir.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "quest"))
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "corpus vazio em {}", dir.display());
+    files
+        .into_iter()
+        .map(|path| {
+            let source = fs::read_to_string(&path).unwrap();
+            (path, source)
+        })
+        .collect()
+}
+
+fn name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().into_owned()
+}
+
+/// AST sem trechos, um nó por linha, para comparar e mostrar em diffs.
+fn normalize(program: &[Statement]) -> String {
+    let mut out = String::new();
+    for statement in program {
+        write_statement(&mut out, statement, 0);
+    }
+    out
+}
+
+fn write_statement(out: &mut String, statement: &Statement, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match &statement.kind {
+        StatementKind::Command(command) => writeln!(out, "{}{}", indent, command).unwrap(),
+        StatementKind::Block(statements) => {
+            writeln!(out, "{}block", indent).unwrap();
+            for statement in statements {
+                write_statement(out, statement, depth + 1);
+            }
+        }
+        StatementKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            writeln!(out, "{}if {}", indent, sexpr(condition)).unwrap();
+            write_statement(out, then_branch, depth + 1);
+            if let Some(else_branch) = else_branch {
+                writeln!(out, "{}else", indent).unwrap();
+                write_statement(out, else_branch, depth + 1);
+            }
+        }
+        StatementKind::While { condition, body } => {
+            writeln!(out, "{}while {}", indent, sexpr(condition)).unwrap();
+            write_statement(out, body, depth + 1);
+        }
+        StatementKind::Repeat { count, body } => {
+            writeln!(out, "{}repeat {}", indent, sexpr(count)).unwrap();
+            write_statement(out, body, depth + 1);
+        }
+        StatementKind::For {
+            init,
+            condition,
+            update,
+            body,
+        } => {
+            writeln!(out, "{}for {}", indent, sexpr(condition)).unwrap();
+            write_statement(out, init, depth + 1);
+            write_statement(out, update, depth + 1);
+            write_statement(out, body, depth + 1);
+        }
+        StatementKind::Let { name, value } => {
+            writeln!(out, "{}let {} {}", indent, name, sexpr(value)).unwrap()
+        }
+        StatementKind::Assign { name, op, value } => {
+            writeln!(out, "{}{} {} {}", indent, name, op, sexpr(value)).unwrap()
+        }
+        StatementKind::Error => writeln!(out, "{}<error>", indent).unwrap(),
+    }
+}
+
+/// Expressão em notação prefixa, com a associatividade explícita.
+fn sexpr(expr: &Expression) -> String {
+    match &expr.kind {
+        ExpressionKind::Identifier(name) => name.clone(),
+        ExpressionKind::Number(n) => n.to_string(),
+        ExpressionKind::Binary { left, op, right } => {
+            format!("({} {} {})", op, sexpr(left), sexpr(right))
+        }
+        ExpressionKind::Unary { op, operand } => format!("({} {})", op, sexpr(operand)),
+        ExpressionKind::Error => "<error>".to_string(),
+    }
+}
+
+fn diff(expected_name: &str, expected: &str, actual_name: &str, actual: &str) -> String {
+    TextDiff::from_lines(expected, actual)
+        .unified_diff()
+        .header(expected_name, actual_name)
+        .to_string()
+}
+
+fn token_lines(tokens: &[Spanned<Token>]) -> String {
+    tokens.iter().map(|t| format!("{:?} {:?}\n", t.value, t.span)).collect()
+}
+
+/// Tokens do primeiro lexer, depois de conferir que os demais concordam.
+fn lex_agreeing(file: &str, source: &str, failures: &mut Vec<String>) -> Option<Vec<Spanned<Token>>> {
+    let lexers = backend::lexers();
+    let reference = lexers.first()?;
+    let expected = (reference.lex)(source);
+    for lexer in &lexers[1..] {
+        let actual = (lexer.lex)(source);
+        if actual == expected {
+            continue;
+        }
+        let text = |result: &backend::LexResult| match result {
+            Ok(tokens) => token_lines(tokens),
+            Err(errors) => errors.iter().map(|e| format!("{}\n", e)).collect(),
+        };
+        failures.push(format!(
+            "{}: lexers divergem\n{}",
+            file,
+            diff(reference.name, &text(&expected), lexer.name, &text(&actual))
+        ));
+    }
+    expected.ok()
+}
+
+type BackendResult = (String, Result<Vec<Statement>, String>);
+
+/// Todos os parsers habilitados, mais a CST convertida para AST.
+fn parse_all(tokens: &[Spanned<Token>], source: &str) -> Vec<BackendResult> {
+    backend::parsers()
+        .iter()
+        .map(|parser: &ParserBackend| {
+            let result = (parser.parse)(tokens.to_vec()).map_err(|errors| {
+                errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ")
+            });
+            (parser.name.to_string(), result)
+        })
+        .chain(parse_cst(source))
+        .collect()
+}
+
+#[cfg(feature = "cst")]
+fn parse_cst(source: &str) -> Option<BackendResult> {
+    let parse = quest_lang::cst::parser::parse(source);
+    let result = if parse.errors().is_empty() {
+        Ok(parse.ast())
+    } else {
+        Err(parse.errors().iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; "))
+    };
+    Some(("CST".to_string(), result))
+}
+
+#[cfg(not(feature = "cst"))]
+fn parse_cst(_source: &str) -> Option<BackendResult> {
+    None
+}
+
+fn report(failures: Vec<String>) {
+    if !failures.is_empty() {
+        panic!("{} divergência(s):\n\n{}", failures.len(), failures.join("\n"));
+    }
+}
+
+#[test]
+fn valid_corpus_parses_identically_in_every_backend() {
+    let mut failures = Vec::new();
+    for (path, source) in corpus("valid") {
+        let file = name(&path);
+        let Some(tokens) = lex_agreeing(&file, &source, &mut failures) else {
+            failures.push(format!("{}: erro léxico em programa válido", file));
+            continue;
+        };
+        let results = parse_all(&tokens, &source);
+        let mut reference: Option<(String, String)> = None;
+        for (backend, result) in results {
+            let program = match result {
+                Ok(program) => normalize(&program),
+                Err(errors) => {
+                    failures.push(format!("{}: {} rejeitou: {}", file, backend, errors));
+                    continue;
+                }
+            };
+            match &reference {
+                None => reference = Some((backend, program)),
+                Some((reference_name, expected)) if *expected != program => failures.push(format!(
+                    "{}: {} e {} divergem\n{}",
+                    file,
+                    reference_name,
+                    backend,
+                    diff(reference_name, expected, &backend, &program)
+                )),
+                Some(_) => {}
+            }
+        }
+    }
+    report(failures);
+}
+
+#[test]
+fn invalid_corpus_is_rejected_by_every_backend() {
+    let mut failures = Vec::new();
+    for (path, source) in corpus("invalid") {
+        let file = name(&path);
+        // Um erro léxico já rejeita o programa; basta os lexers concordarem
+        let Some(tokens) = lex_agreeing(&file, &source, &mut failures) else {
+            continue;
+        };
+        for (backend, result) in parse_all(&tokens, &source) {
+            if let Ok(program) = result {
+                failures.push(format!(
+                    "{}: {} aceitou um programa inválido\n{}",
+                    file,
+                    backend,
+                    normalize(&program)
+                ));
+            }
+        }
+    }
+    report(failures);
+}
+
+#[cfg(feature = "cst")]
+#[test]
+fn cst_round_trips_every_corpus_file() {
+    let mut failures = Vec::new();
+    for (path, source) in corpus("valid").into_iter().chain(corpus("invalid")) {
+        let text = quest_lang::cst::parser::parse(&source).syntax().text().to_string();
+        if text != source {
+            failures.push(format!(
+                "{}: texto da CST difere do original\n{}",
+                name(&path),
+                diff("original", &source, "cst", &text)
+            ));
+        }
+    }
+    report(failures);
+}
+
+/// Confere que a formatação não muda a AST e que formatar de novo não muda
+/// o texto. Devolve a saída do formatador.
+#[cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+fn check_formatter(file: &str, source: &str, failures: &mut Vec<String>) -> String {
+    use quest_lang::formatter::format;
+
+    let formatted = format(source).expect("programa válido");
+    let parse = |code: &str| {
+        let tokens = quest_lang::lexer_manual::lexer_impl::lex(code).unwrap();
+        quest_lang::parser_manual::parser_impl::parse(tokens).map(|p| normalize(&p))
+    };
+    match parse(&formatted) {
+        Ok(program) if program != parse(source).unwrap() => failures.push(format!(
+            "{}: a formatação mudou a AST\n{}",
+            file,
+            diff("original", &parse(source).unwrap(), "formatado", &program)
+        )),
+        Ok(_) => {}
+        Err(errors) => failures.push(format!("{}: saída do formatador não parseia: {:?}", file, errors)),
+    }
+    let again = format(&formatted).expect("saída do formatador");
+    if again != formatted {
+        failures.push(format!(
+            "{}: formatador não é idempotente\n{}",
+            file,
+            diff("1ª passada", &formatted, "2ª passada", &again)
+        ));
+    }
+    formatted
+}
+
+#[cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+#[test]
+fn formatter_keeps_the_ast_and_is_idempotent() {
+    let mut failures = Vec::new();
+    for (path, source) in corpus("valid") {
+        check_formatter(&name(&path), &source, &mut failures);
+    }
+    report(failures);
+}
+
+/// Comentário no fim da linha fica com o statement que vem logo antes dele,
+/// mesmo com outros statements antes na mesma linha.
+#[cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+#[test]
+fn formatter_keeps_trailing_comments_on_the_preceding_statement() {
+    let cases = [
+        ("jump move_up // two\nattack", "jump\nmove_up // two\nattack\n"),
+        ("let x = 1; let y = 2; // about y", "let x = 1;\nlet y = 2; // about y\n"),
+        ("repeat (3) { jump } attack // c", "repeat (3) {\n    jump\n}\nattack // c\n"),
+    ];
+    let mut failures = Vec::new();
+    for (source, expected) in cases {
+        let formatted = check_formatter(source, source, &mut failures);
+        if formatted != expected {
+            failures.push(format!("{:?}\n{}", source, diff("esperado", expected, "formatado", &formatted)));
+        }
+    }
+    report(failures);
+}
+
+/// Comentário no meio de um statement não cria linhas em branco: só contam
+/// as quebras de linha logo antes de cada item.
+#[cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+#[test]
+fn formatter_adds_no_blank_lines_around_comments_inside_statements() {
+    let cases = [
+        ("if (x) {\n jump\n} // t\nelse {\n defend\n}", "if (x) {\n    jump\n} else {\n    // t\n    defend\n}\n"),
+        ("while (x) // w\n{ jump }", "while (x) {\n    // w\n    jump\n}\n"),
+        ("if (a // c\n) jump\nattack", "if (a) jump\n// c\nattack\n"),
+        ("jump\n\n// a\n\nattack", "jump\n\n// a\n\nattack\n"),
+    ];
+    let mut failures = Vec::new();
+    for (source, expected) in cases {
+        let formatted = check_formatter(source, source, &mut failures);
+        if formatted != expected {
+            failures.push(format!("{:?}\n{}", source, diff("esperado", expected, "formatado", &formatted)));
+        }
+    }
+    report(failures);
+}
+
+#[cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+#[test]
+fn formatter_separates_nested_negations() {
+    let mut failures = Vec::new();
+    let formatted = check_formatter("nested", "let x = - -1;\nlet y = -!-x;", &mut failures);
+    assert_eq!(formatted, "let x = -(-1);\nlet y = -!-x;\n");
+    report(failures);
+}
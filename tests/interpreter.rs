@@ -0,0 +1,134 @@
+//! Semântica do interpretador, com um estado de jogo mínimo que registra os
+//! comandos recebidos.
+
+#![cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+
+use std::collections::HashMap;
+
+use quest_lang::ast::{Command, Statement};
+use quest_lang::error::RuntimeError;
+use quest_lang::interpreter::{run, GameState, Interpreter};
+use quest_lang::lexer_manual::lexer_impl::lex;
+use quest_lang::parser_manual::parser_impl::parse;
+
+/// Estado de teste: cada `attack` derruba um inimigo; `move_up` num lugar
+/// sem saída falha.
+#[derive(Default)]
+struct Recorder {
+    commands: Vec<Command>,
+    values: HashMap<String, i64>,
+}
+
+impl Recorder {
+    fn with(values: &[(&str, i64)]) -> Self {
+        Recorder {
+            commands: Vec::new(),
+            values: values.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+        }
+    }
+}
+
+impl GameState for Recorder {
+    fn execute(&mut self, command: Command) -> Result<(), String> {
+        if command == Command::MoveUp && self.values.get("wall") == Some(&1) {
+            return Err("there is a wall".to_string());
+        }
+        if command == Command::Attack {
+            *self.values.entry("enemy".to_string()).or_default() -= 1;
+        }
+        self.commands.push(command);
+        Ok(())
+    }
+
+    fn read(&mut self, name: &str) -> Option<i64> {
+        self.values.get(name).copied()
+    }
+}
+
+fn program(code: &str) -> Vec<Statement> {
+    parse(lex(code).unwrap()).unwrap()
+}
+
+#[test]
+fn runs_commands_and_branches_in_order() {
+    let mut state = Recorder::with(&[("hero", 3), ("enemy", 0)]);
+    let code = "move_left if (hero > 2) { jump } else { defend } if (enemy) { attack } else if (!enemy) { move_right }";
+    run(&program(code), &mut state).unwrap();
+    assert_eq!(
+        state.commands,
+        [Command::MoveLeft, Command::Jump, Command::MoveRight]
+    );
+}
+
+#[test]
+fn loops_see_state_changed_by_commands() {
+    let mut state = Recorder::with(&[("enemy", 3)]);
+    run(&program("while (enemy > 0) { attack } for (let i = 0; enemy < 0; i += 1) { defend }"), &mut state).unwrap();
+    assert_eq!(state.commands, [Command::Attack; 3]);
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    let mut state = Recorder::default();
+    // `missing` não existe, mas nunca é avaliado
+    run(&program("if (0 && missing) { jump } if (1 || missing) { defend }"), &mut state).unwrap();
+    assert_eq!(state.commands, [Command::Defend]);
+}
+
+#[test]
+fn reports_runtime_errors_with_their_span() {
+    let code = "if (1 / (2 - 2)) { jump }";
+    let error = run(&program(code), &mut Recorder::default()).unwrap_err();
+    assert!(matches!(error, RuntimeError::DivisionByZero { .. }));
+    assert_eq!(&code[error.span().start..error.span().end], "2 - 2");
+
+    let error = run(&program("jump if (ghost) {}"), &mut Recorder::default()).unwrap_err();
+    assert!(matches!(error, RuntimeError::UnknownIdentifier { ref name, .. } if name == "ghost"));
+
+    let mut state = Recorder::with(&[("wall", 1)]);
+    let error = run(&program("jump move_up attack"), &mut state).unwrap_err();
+    assert!(matches!(error, RuntimeError::CommandFailed { command: Command::MoveUp, .. }));
+    assert_eq!(state.commands, [Command::Jump]);
+}
+
+#[test]
+fn step_limit_stops_infinite_loops() {
+    let mut state = Recorder::default();
+    let program = program("while (1) { jump }");
+    let error = Interpreter::new(&mut state)
+        .with_step_limit(100)
+        .run(&program)
+        .unwrap_err();
+    assert!(matches!(error, RuntimeError::StepLimitExceeded { limit: 100, .. }));
+    assert_eq!(state.commands.len(), 49);
+}
+
+#[test]
+fn variables_are_scoped_per_block() {
+    let mut state = Recorder::with(&[("enemy", 2)]);
+    let code = "let n = 0; for (let i = 0; i < 3; i += 1) { n += i; let enemy = 9; } \
+                { let n = 100; if (n == 100 && enemy == 2) { jump } } if (n == 3) { defend }";
+    let mut interpreter = Interpreter::new(&mut state);
+    interpreter.run(&program(code)).unwrap();
+    assert_eq!(interpreter.variable("n"), Some(3));
+    assert_eq!(interpreter.variable("i"), None);
+    assert_eq!(state.commands, [Command::Jump, Command::Defend]);
+
+    for code in ["{ let x = 1; } x = 2;", "for (let x = 0; x < 1; x += 1) {} if (x) {}"] {
+        let error = run(&program(code), &mut Recorder::default()).unwrap_err();
+        assert!(matches!(error, RuntimeError::UnknownIdentifier { ref name, .. } if name == "x"));
+    }
+}
+
+#[test]
+fn repeat_evaluates_its_count_once() {
+    // O corpo muda `enemy`, mas a contagem já foi lida
+    let mut state = Recorder::with(&[("enemy", 3)]);
+    run(&program("repeat (enemy) { attack } repeat (-2) { jump }"), &mut state).unwrap();
+    assert_eq!(state.commands, [Command::Attack; 3]);
+
+    let mut state = Recorder::default();
+    let code = "let n = 0; repeat (2) { repeat (3) { n += 1; } } if (n == 6) { defend }";
+    run(&program(code), &mut state).unwrap();
+    assert_eq!(state.commands, [Command::Defend]);
+}
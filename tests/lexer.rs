@@ -0,0 +1,55 @@
+//! Posições (linha:coluna) reportadas pelos lexers, conferidas contra valores
+//! calculados à mão: quebras de linha, tabulações (uma coluna) e caracteres
+//! de mais de um byte (a coluna conta caracteres).
+
+use quest_lang::backend;
+use quest_lang::error::LexErrorKind;
+use quest_lang::span::Position;
+
+#[test]
+fn tokens_report_their_line_and_column() {
+    let source = "// olá, café\n\tjump\tattack\n\n  // é\n  x >= 10";
+    let expected = [
+        ("// olá, café", 1, 1),
+        ("jump", 2, 2),
+        ("attack", 2, 7),
+        ("// é", 4, 3),
+        ("x", 5, 3),
+        (">=", 5, 5),
+        ("10", 5, 8),
+    ];
+    for lexer in backend::lexers() {
+        let tokens = (lexer.lex)(source).unwrap();
+        let actual: Vec<(&str, usize, usize)> = tokens
+            .iter()
+            .map(|t| (&source[t.span.start..t.span.end], t.position.line, t.position.column))
+            .collect();
+        assert_eq!(actual, expected, "lexer {}", lexer.name);
+        for token in &tokens {
+            assert_eq!(Position::of(source, token.span.start), token.position);
+        }
+    }
+}
+
+#[test]
+fn lex_errors_report_their_line_and_column() {
+    let source = "move_up é¬ &\n\t¬ jump";
+    let expected = [
+        (LexErrorKind::UnexpectedCharacter, "é", 8..10, 1, 9),
+        (LexErrorKind::UnexpectedCharacter, "¬", 10..12, 1, 10),
+        (LexErrorKind::IncompleteOperator, "&", 13..14, 1, 12),
+        (LexErrorKind::UnexpectedCharacter, "¬", 16..18, 2, 2),
+    ];
+    for lexer in backend::lexers() {
+        let errors = (lexer.lex)(source).unwrap_err();
+        let actual: Vec<_> = errors
+            .iter()
+            .map(|e| {
+                let span = e.span.start..e.span.end;
+                (e.kind.clone(), e.slice.as_str(), span, e.position.line, e.position.column)
+            })
+            .collect();
+        assert_eq!(actual, expected, "lexer {}", lexer.name);
+        assert_eq!(errors[3].to_string(), "unexpected character `¬` at 2:2");
+    }
+}
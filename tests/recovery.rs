@@ -0,0 +1,180 @@
+//! Recuperação de erros do parser Chumsky: a AST parcial que sobra de
+//! programas malformados e quantos erros cada um reporta.
+
+#![cfg(all(feature = "logos-lexer", feature = "chumsky-parser"))]
+
+use quest_lang::ast::{Statement, StatementKind};
+use quest_lang::error::{Expected, ParseError};
+use quest_lang::formatter::expression;
+use quest_lang::lexer::lexer_impl::lex;
+use quest_lang::parser::parser_impl::parse_tokens_recovery;
+use quest_lang::span::Span;
+use quest_lang::token::Token;
+
+/// Statements recuperados, um por item, numa forma compacta; trechos que
+/// não puderam ser parseados aparecem como `<error>` e expressões inválidas
+/// como `()` vazios.
+fn recover(source: &str) -> (Vec<String>, Vec<ParseError>) {
+    let (ast, errors) = parse_tokens_recovery(lex(source).unwrap());
+    let ast = ast.expect("AST parcial");
+    (ast.iter().map(shape).collect(), errors)
+}
+
+fn shape(statement: &Statement) -> String {
+    match &statement.kind {
+        StatementKind::Command(command) => command.to_string(),
+        StatementKind::Block(statements) => {
+            let inner: Vec<String> = statements.iter().map(shape).collect();
+            format!("{{ {} }}", inner.join(" "))
+        }
+        StatementKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            let mut text = format!("if ({}) {}", expression(condition), shape(then_branch));
+            if let Some(else_branch) = else_branch {
+                text.push_str(&format!(" else {}", shape(else_branch)));
+            }
+            text
+        }
+        StatementKind::While { condition, body } => {
+            format!("while ({}) {}", expression(condition), shape(body))
+        }
+        StatementKind::Repeat { count, body } => {
+            format!("repeat ({}) {}", expression(count), shape(body))
+        }
+        StatementKind::For {
+            init,
+            condition,
+            update,
+            body,
+        } => format!(
+            "for ({}; {}; {}) {}",
+            shape(init),
+            expression(condition),
+            shape(update),
+            shape(body)
+        ),
+        StatementKind::Let { name, value } => format!("let {} = {}", name, expression(value)),
+        StatementKind::Assign { name, op, value } => {
+            format!("{} {} {}", name, op, expression(value))
+        }
+        StatementKind::Error => "<error>".to_string(),
+    }
+}
+
+#[test]
+fn unclosed_blocks_keep_their_statements() {
+    let (ast, errors) = recover("while (1) { jump");
+    assert_eq!(ast, ["while (1) { jump }"]);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0],
+        ParseError::UnclosedDelimiter { delimiter: Token::LBrace, open, expected, .. }
+            if *open == Span::new(10, 11) && *expected == [Expected::Token(Token::RBrace)]
+    ));
+
+    // Só o bloco mais externo fica aberto
+    let (ast, errors) = recover("repeat (3) { if (x) { jump }");
+    assert_eq!(ast, ["repeat (3) { if (x) { jump } }"]);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0],
+        ParseError::UnclosedDelimiter { open, .. } if *open == Span::new(11, 12)
+    ));
+}
+
+#[test]
+fn invalid_statements_become_error_nodes() {
+    let (ast, errors) = recover("jump 42 attack");
+    assert_eq!(ast, ["jump", "<error>", "attack"]);
+    assert_eq!(errors.len(), 1);
+
+    let (ast, errors) = recover("{ jump ) attack }");
+    assert_eq!(ast, ["{ jump <error> attack }"]);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn invalid_headers_keep_the_body() {
+    let (ast, errors) = recover("if (hero +) { attack } defend");
+    assert_eq!(ast, ["if () { attack }", "defend"]);
+    assert_eq!(errors.len(), 1);
+
+    let (ast, errors) = recover("for (let i = 0; i < 3) { attack } defend");
+    assert_eq!(ast, ["for (<error>; ; <error>) { attack }", "defend"]);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn stray_closing_braces_become_error_nodes() {
+    let (ast, errors) = recover("move_up } move_down");
+    assert_eq!(ast, ["move_up", "<error>", "move_down"]);
+    assert!(matches!(
+        &errors[..],
+        [ParseError::UnexpectedToken { found: Token::RBrace, expected, .. }]
+            if *expected == [Expected::Statement]
+    ));
+}
+
+#[test]
+fn every_error_in_the_program_is_reported() {
+    let (ast, errors) = recover("let = 1; jump while () { attack }");
+    assert_eq!(ast, ["<error>", "jump", "while () { attack }"]);
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].span(), Span::new(4, 5));
+    assert_eq!(errors[1].span(), Span::new(21, 22));
+}
+
+/// Mensagem e posição de cada erro, na ordem em que são reportados.
+fn messages(errors: &[ParseError]) -> Vec<String> {
+    errors.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn missing_bodies_keep_the_header_errors() {
+    let (ast, errors) = recover("if ( >= x )");
+    assert_eq!(ast, ["if () <error>"]);
+    assert_eq!(
+        messages(&errors),
+        [
+            "unexpected `>=` at 1:6, expected an expression",
+            "unexpected end of input at 1:12, expected a statement",
+        ]
+    );
+
+    let (ast, errors) = recover("if ( = + { 1 < y y || >= ) }");
+    assert_eq!(ast, ["if () <error>", "<error>"]);
+    assert_eq!(
+        messages(&errors),
+        [
+            "unexpected `=` at 1:6, expected an expression",
+            "unexpected `}` at 1:28, expected a statement",
+        ]
+    );
+
+    // Cada erro uma vez, na ordem do código
+    let (_, errors) = recover("repeat ( jump attack attack ) } >= let let x");
+    assert_eq!(
+        messages(&errors),
+        [
+            "unexpected `jump` at 1:10, expected an expression",
+            "unexpected `}` at 1:31, expected a statement",
+            "unexpected `>=` at 1:33, expected a statement",
+            "unexpected `let` at 1:40, expected an identifier",
+            "unexpected end of input at 1:45, expected `=`",
+        ]
+    );
+}
+
+#[test]
+fn else_requires_a_statement() {
+    let (ast, errors) = recover("if (x) jump else");
+    assert_eq!(ast, ["if (x) jump else <error>"]);
+    assert_eq!(messages(&errors), ["unexpected end of input at 1:17, expected a statement"]);
+
+    let (ast, errors) = recover("{ if (x) jump else } attack");
+    assert_eq!(ast, ["{ if (x) jump else <error> }", "attack"]);
+    assert_eq!(messages(&errors), ["unexpected `}` at 1:20, expected a statement"]);
+}
@@ -0,0 +1,69 @@
+//! Associação de comentários aos statements com `nearest_statement`.
+
+#![cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+
+use quest_lang::lexer_manual::lexer_impl::lex;
+use quest_lang::parser_manual::parser_impl::parse;
+use quest_lang::trivia::{nearest_statement, split_comments};
+
+/// Texto do statement associado a cada comentário, na ordem do código.
+fn nearest(source: &str) -> Vec<Option<&str>> {
+    let (tokens, comments) = split_comments(lex(source).unwrap());
+    let program = parse(tokens).unwrap();
+    comments
+        .iter()
+        .map(|comment| {
+            nearest_statement(&program, comment).map(|s| &source[s.span.start..s.span.end])
+        })
+        .collect()
+}
+
+#[test]
+fn comments_before_and_between_take_the_next_statement() {
+    assert_eq!(nearest("// a\njump\nattack"), [Some("jump")]);
+    assert_eq!(nearest("jump\n// b\n\nattack"), [Some("attack")]);
+    assert_eq!(nearest("jump // c\nattack"), [Some("attack")]);
+    assert_eq!(nearest("let x = 1; // d\nx += 2;"), [Some("x += 2;")]);
+}
+
+#[test]
+fn comments_inside_take_the_innermost_statement() {
+    let source =
+        "if (hero) {\n    // a\n    while (enemy) {\n        // b\n        attack\n    }\n}";
+    assert_eq!(
+        nearest(source),
+        [
+            Some("while (enemy) {\n        // b\n        attack\n    }"),
+            Some("attack")
+        ]
+    );
+
+    // Depois do último statement do bloco: o anterior, não o que vem após o bloco
+    let source = "while (enemy) {\n    attack\n    // c\n}\njump";
+    assert_eq!(nearest(source), [Some("attack")]);
+
+    // Bloco vazio: o próprio bloco
+    assert_eq!(
+        nearest("repeat (3) {\n    // d\n}"),
+        [Some("{\n    // d\n}")]
+    );
+
+    // Cabeçalho do for: a parte seguinte do cabeçalho
+    assert_eq!(
+        nearest("for (let i = 0; // e\n i < 3; i += 1) {}"),
+        [Some("i += 1")]
+    );
+}
+
+#[test]
+fn comments_after_everything_take_the_last_statement() {
+    assert_eq!(nearest("jump\nattack\n// a"), [Some("attack")]);
+    assert_eq!(
+        nearest("if (hero) { jump } else { defend }\n// b\n// c"),
+        [
+            Some("if (hero) { jump } else { defend }"),
+            Some("if (hero) { jump } else { defend }"),
+        ]
+    );
+    assert_eq!(nearest("// só comentários"), [None]);
+}
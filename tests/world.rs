@@ -0,0 +1,132 @@
+//! Mundo em grade: leitura e impressão de mapas, o efeito de cada comando e
+//! os sensores que os programas leem.
+
+use quest_lang::ast::Command;
+use quest_lang::interpreter::GameState;
+use quest_lang::sensor::{Game, Sensors};
+use quest_lang::world::{Facing, MapError, Point, Tile, World, MAX_HEALTH};
+
+const MAP: &str = "\
+#######
+#>.O.$#
+#..E..#
+#######
+";
+
+fn world(map: &str) -> World {
+    map.parse().unwrap()
+}
+
+fn grid(world: &World) -> String {
+    let text = world.to_string();
+    text.split_once("---\n").unwrap().1.to_string()
+}
+
+#[test]
+fn loads_and_prints_maps_with_their_legend() {
+    let world = world(MAP);
+    assert_eq!((world.width(), world.height()), (7, 4));
+    assert_eq!(world.hero.position, Point::new(1, 1));
+    assert_eq!(world.hero.facing, Facing::Right);
+    assert_eq!(world.tile(Point::new(1, 1)), Tile::Floor);
+    assert_eq!(world.tile(Point::new(3, 2)), Tile::Enemy);
+    assert_eq!(grid(&world), MAP);
+    assert_eq!(world.to_string().parse::<World>().unwrap(), world);
+
+    let custom = "X = wall\n@ = hero right\n_ = floor\n---\nXXXX\nX@_X\nXXXX\n";
+    let world: World = custom.parse().unwrap();
+    assert_eq!(world.tile(Point::new(2, 1)), Tile::Floor);
+    assert_eq!(grid(&world), "XXXX\nX@_X\nXXXX\n");
+    assert_eq!(world.to_string().parse::<World>().unwrap(), world);
+}
+
+#[test]
+fn rejects_malformed_maps() {
+    let error = |map: &str| map.parse::<World>().unwrap_err();
+    assert_eq!(error("#.#\n#?#\n"), MapError::UnknownTile { c: '?', line: 2, column: 2 });
+    assert_eq!(error("###\n#^\n"), MapError::RaggedRow { line: 2 });
+    assert_eq!(error("###\n"), MapError::MissingHero);
+    assert_eq!(error("^v\n"), MapError::MultipleHeroes { line: 1, column: 2 });
+    assert_eq!(error("\n\n"), MapError::EmptyMap);
+    assert!(matches!(error("# = lava\n---\n^\n"), MapError::InvalidLegend { line: 1, .. }));
+    assert!(matches!(error("# = wall\n. = wall\n---\n^\n"), MapError::InvalidLegend { line: 2, .. }));
+}
+
+#[test]
+fn commands_move_jump_and_collect() {
+    let mut world = world(MAP);
+    // A parede bloqueia sem erro; o buraco é pulado e o tesouro coletado
+    for command in [Command::MoveUp, Command::MoveRight, Command::Jump, Command::MoveRight] {
+        world.apply(command).unwrap();
+    }
+    assert_eq!(world.hero.position, Point::new(5, 1));
+    assert_eq!(world.treasure, 1);
+    assert_eq!(world.tile(Point::new(5, 1)), Tile::Floor);
+
+    let mut world = self::world(MAP);
+    world.apply(Command::MoveRight).unwrap();
+    assert!(world.apply(Command::MoveRight).is_err());
+    assert!(!world.is_alive());
+    assert!(world.apply(Command::Defend).is_err());
+}
+
+#[test]
+fn enemies_hurt_unless_defended_or_defeated() {
+    let mut world = world(MAP);
+    world.apply(Command::MoveDown).unwrap();
+    world.apply(Command::MoveRight).unwrap();
+    assert_eq!(world.hero.health, MAX_HEALTH - 1);
+    world.apply(Command::Defend).unwrap();
+    assert_eq!(world.hero.health, MAX_HEALTH - 1);
+    // Inimigo bloqueia o caminho até ser derrotado
+    world.apply(Command::MoveRight).unwrap();
+    assert_eq!(world.hero.position, Point::new(2, 2));
+    world.apply(Command::Attack).unwrap();
+    assert_eq!(world.tile(Point::new(3, 2)), Tile::Floor);
+    assert_eq!(world.hero.health, MAX_HEALTH - 2);
+    world.apply(Command::MoveRight).unwrap();
+    assert_eq!(world.hero.health, MAX_HEALTH - 2);
+}
+
+#[test]
+fn builtin_sensors_read_the_world() {
+    let mut game = Game::new(world(MAP));
+    assert_eq!(game.read("hero"), Some(MAX_HEALTH));
+    assert_eq!(game.read("enemy"), Some(3));
+    assert_eq!(game.read("treasure"), Some(0));
+    assert_eq!(game.read("ghost"), None);
+
+    game.world.hero.position = Point::new(4, 1);
+    assert_eq!(game.read("treasure"), Some(1));
+    game.world.set_tile(Point::new(3, 2), Tile::Floor);
+    assert_eq!(game.read("enemy"), Some(0));
+}
+
+#[test]
+fn hosts_can_register_sensors() {
+    let mut game = Game::new(world(MAP));
+    game.sensors
+        .register("x", |world| world.hero.position.x as i64)
+        .register("hero", |_| 99);
+    assert_eq!(game.read("x"), Some(1));
+    assert_eq!(game.read("hero"), Some(99));
+    assert_eq!(game.sensors.names().collect::<Vec<_>>(), ["hero", "enemy", "treasure", "x"]);
+    assert_eq!(Sensors::empty().read("hero", &game.world), None);
+}
+
+#[cfg(all(feature = "manual-lexer", feature = "manual-parser"))]
+#[test]
+fn programs_steer_the_hero_with_sensors() {
+    use quest_lang::interpreter::run;
+    use quest_lang::lexer_manual::lexer_impl::lex;
+    use quest_lang::parser_manual::parser_impl::parse;
+
+    let code = "move_down while (enemy > 1) { move_right } if (enemy == 1) { attack } \
+                move_right move_right move_up if (!treasure) { jump }";
+    let mut game = Game::new(world(MAP));
+    run(&parse(lex(code).unwrap()).unwrap(), &mut game).unwrap();
+    assert_eq!(game.world.find(Tile::Enemy).count(), 0);
+    assert_eq!(game.world.hero.position, Point::new(4, 1));
+    assert_eq!(game.world.treasure, 0);
+    assert_eq!(game.read("treasure"), Some(1));
+}